@@ -0,0 +1,196 @@
+//! # Export Chunking
+//!
+//! Splits a rendered export into several size-bounded parts, for chat tools
+//! that cap the size of a single paste.
+//!
+//! Parts are filled file by file, so splits fall on file boundaries wherever
+//! possible. A file that does not fit into a part on its own is split on line
+//! boundaries, and each continuation gets its own `File:` banner.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use tree_txt::chunker::{Section, split_into_parts};
+//! use tree_txt::tokens::SizeUnit;
+//!
+//! let sections = vec![
+//!     Section::file("a.rs", "fn a() {}\n".repeat(20)),
+//!     Section::file("b.rs", "fn b() {}\n".repeat(20)),
+//! ];
+//!
+//! let parts = split_into_parts(&sections, 400, SizeUnit::Bytes);
+//! assert_eq!(parts.len(), 2);
+//! assert!(parts[0].text.starts_with("# Codebase Export - part 1 of 2"));
+//! assert_eq!(parts[1].files, vec!["b.rs".to_string()]);
+//! ```
+
+use crate::tokens::{SizeUnit, measure};
+
+const FILES_HEADING: &str = "Files in this part:\n";
+
+/// A unit of export content: either a file's section or other content such
+/// as the export header and directory tree.
+#[derive(Debug, Clone)]
+pub struct Section {
+    /// Display path of the file, or `None` for non-file content
+    pub file: Option<String>,
+    /// Rendered text of the section
+    pub text: String,
+}
+
+impl Section {
+    /// Creates a section holding a file's rendered contents.
+    pub fn file(path: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            file: Some(path.into()),
+            text: text.into(),
+        }
+    }
+
+    /// Creates a section holding non-file content.
+    pub fn other(text: impl Into<String>) -> Self {
+        Self {
+            file: None,
+            text: text.into(),
+        }
+    }
+}
+
+/// One part of a chunked export.
+#[derive(Debug, Clone)]
+pub struct Part {
+    /// Files (or file pieces) contained in this part
+    pub files: Vec<String>,
+    /// Full text of the part, including its part header
+    pub text: String,
+}
+
+/// Splits `sections` into parts no larger than `limit` (measured in `unit`).
+///
+/// A single line longer than the limit cannot be split and produces an
+/// oversized part.
+pub fn split_into_parts(sections: &[Section], limit: usize, unit: SizeUnit) -> Vec<Part> {
+    // Reserve room for the part header, estimated with generous part numbers
+    let header_overhead = measure(&part_header(999, 999, &[]), unit) + measure(FILES_HEADING, unit);
+    let budget = limit.saturating_sub(header_overhead).max(1);
+
+    let mut bodies: Vec<(Vec<String>, String)> = Vec::new();
+    let mut files = Vec::new();
+    let mut body = String::new();
+    let mut used = 0;
+
+    for section in sections {
+        for piece in split_section(section, budget, unit) {
+            let cost = measure(&piece.text, unit)
+                + piece
+                    .file
+                    .as_ref()
+                    .map_or(0, |file| measure(&file_entry(file), unit));
+
+            if !body.is_empty() && used + cost > budget {
+                bodies.push((std::mem::take(&mut files), std::mem::take(&mut body)));
+                used = 0;
+            }
+
+            body.push_str(&piece.text);
+            used += cost;
+            if let Some(file) = piece.file {
+                files.push(file);
+            }
+        }
+    }
+    if !body.is_empty() {
+        bodies.push((files, body));
+    }
+
+    let total = bodies.len();
+    bodies
+        .into_iter()
+        .enumerate()
+        .map(|(index, (files, body))| {
+            let mut text = part_header(index + 1, total, &files);
+            text.push_str(&body);
+            Part { files, text }
+        })
+        .collect()
+}
+
+/// Splits a section into pieces that each fit in `budget`, breaking on lines.
+///
+/// Pieces of a file are labelled `path [k/m]`, and every piece after the
+/// first starts with a continuation banner.
+fn split_section(section: &Section, budget: usize, unit: SizeUnit) -> Vec<Section> {
+    let entry_cost = section
+        .file
+        .as_ref()
+        .map_or(0, |file| measure(&file_entry(file), unit));
+    if measure(&section.text, unit) + entry_cost <= budget {
+        return vec![section.clone()];
+    }
+
+    // Reserve room for the labels and banners added once the piece count is known
+    let overhead = section.file.as_ref().map_or(0, |file| {
+        let label = piece_label(file, 999, 999);
+        measure(&file_entry(&label), unit) + measure(&continuation_banner(&label), unit)
+    });
+    let piece_budget = budget.saturating_sub(overhead).max(1);
+
+    let mut groups: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut used = 0;
+    for line in section.text.split_inclusive('\n') {
+        let cost = measure(line, unit);
+        if !current.is_empty() && used + cost > piece_budget {
+            groups.push(std::mem::take(&mut current));
+            used = 0;
+        }
+        current.push_str(line);
+        used += cost;
+    }
+    if !current.is_empty() {
+        groups.push(current);
+    }
+
+    let total = groups.len();
+    groups
+        .into_iter()
+        .enumerate()
+        .map(|(index, text)| match &section.file {
+            Some(file) => {
+                let label = piece_label(file, index + 1, total);
+                let text = if index == 0 {
+                    text
+                } else {
+                    continuation_banner(&label) + &text
+                };
+                Section::file(label, text)
+            }
+            None => Section::other(text),
+        })
+        .collect()
+}
+
+fn piece_label(file: &str, index: usize, total: usize) -> String {
+    format!("{file} [{index}/{total}]")
+}
+
+fn continuation_banner(label: &str) -> String {
+    let separator = "─".repeat(60);
+    format!("{separator}\nFile: {label} (continued)\n{separator}\n\n")
+}
+
+fn part_header(index: usize, total: usize, files: &[String]) -> String {
+    let mut header = format!("# Codebase Export - part {index} of {total}\n");
+    if !files.is_empty() {
+        header.push_str(FILES_HEADING);
+        for file in files {
+            header.push_str(&file_entry(file));
+        }
+    }
+    header.push('\n');
+    header
+}
+
+fn file_entry(file: &str) -> String {
+    format!("  - {file}\n")
+}
@@ -0,0 +1,179 @@
+//! # Clipboard Output
+//!
+//! Copies a rendered export to the system clipboard.
+//!
+//! Backends are tried in order until one succeeds:
+//! - **OSC 52** terminal escape sequence, when running over SSH
+//! - `pbcopy` on macOS and `clip` on Windows
+//! - `wl-copy` on Wayland
+//! - `xclip` or `xsel` on X11
+//! - **OSC 52** as a last resort when attached to a terminal
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use tree_txt::clipboard;
+//!
+//! let backend = clipboard::copy("fn main() {}")?;
+//! println!("Copied using {backend}");
+//! # Ok::<(), tree_txt::TreeTxtError>(())
+//! ```
+
+use crate::error::{Result, TreeTxtError};
+use base64::Engine;
+use std::env;
+use std::fmt;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// The mechanism used to reach the clipboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// OSC 52 escape sequence interpreted by the terminal emulator
+    Osc52,
+    /// `wl-copy` from wl-clipboard (Wayland)
+    WlCopy,
+    /// `xclip` (X11)
+    Xclip,
+    /// `xsel` (X11)
+    Xsel,
+    /// `pbcopy` (macOS)
+    Pbcopy,
+    /// `clip` (Windows)
+    Clip,
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Osc52 => "OSC 52",
+            Self::WlCopy => "wl-copy",
+            Self::Xclip => "xclip",
+            Self::Xsel => "xsel",
+            Self::Pbcopy => "pbcopy",
+            Self::Clip => "clip",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Copies `text` to the clipboard, returning the backend that was used.
+///
+/// # Errors
+///
+/// Returns [`TreeTxtError::ClipboardUnavailable`] if no backend could be used,
+/// listing every backend that was tried.
+pub fn copy(text: &str) -> Result<Backend> {
+    let mut attempts = Vec::new();
+
+    for backend in candidates() {
+        match try_backend(backend, text) {
+            Ok(()) => return Ok(backend),
+            Err(e) => attempts.push(format!("{backend}: {e}")),
+        }
+    }
+
+    Err(TreeTxtError::ClipboardUnavailable(if attempts.is_empty() {
+        "no clipboard backend available for this platform".to_string()
+    } else {
+        attempts.join("; ")
+    }))
+}
+
+fn candidates() -> Vec<Backend> {
+    let mut backends = Vec::new();
+    let has_env = |name: &str| env::var_os(name).is_some_and(|value| !value.is_empty());
+
+    // Over SSH, local clipboard tools would target the remote machine
+    if has_env("SSH_TTY") || has_env("SSH_CONNECTION") {
+        backends.push(Backend::Osc52);
+    }
+
+    if cfg!(target_os = "macos") {
+        backends.push(Backend::Pbcopy);
+    } else if cfg!(windows) {
+        backends.push(Backend::Clip);
+    } else {
+        if has_env("WAYLAND_DISPLAY") {
+            backends.push(Backend::WlCopy);
+        }
+        if has_env("DISPLAY") {
+            backends.push(Backend::Xclip);
+            backends.push(Backend::Xsel);
+        }
+    }
+
+    if !backends.contains(&Backend::Osc52) && terminal_supports_osc52() {
+        backends.push(Backend::Osc52);
+    }
+
+    backends
+}
+
+fn terminal_supports_osc52() -> bool {
+    let dumb = env::var("TERM").is_ok_and(|term| term == "dumb");
+    !dumb && (io::stderr().is_terminal() || io::stdout().is_terminal())
+}
+
+fn try_backend(backend: Backend, text: &str) -> io::Result<()> {
+    match backend {
+        Backend::Osc52 => write_osc52(text),
+        Backend::WlCopy => pipe_to("wl-copy", &[], text),
+        Backend::Xclip => pipe_to("xclip", &["-selection", "clipboard"], text),
+        Backend::Xsel => pipe_to("xsel", &["--clipboard", "--input"], text),
+        Backend::Pbcopy => pipe_to("pbcopy", &[], text),
+        Backend::Clip => pipe_to("clip", &[], text),
+    }
+}
+
+fn pipe_to(program: &str, args: &[&str], text: &str) -> io::Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("{program} exited with {status}")))
+    }
+}
+
+fn write_osc52(text: &str) -> io::Result<()> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let mut sequence = format!("\x1b]52;c;{encoded}\x07");
+
+    // tmux only forwards escape sequences wrapped in a DCS passthrough
+    if env::var_os("TMUX").is_some() {
+        sequence = format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"));
+    }
+
+    let mut terminal = open_terminal()?;
+    terminal.write_all(sequence.as_bytes())?;
+    terminal.flush()
+}
+
+#[cfg(unix)]
+fn open_terminal() -> io::Result<Box<dyn Write>> {
+    match std::fs::OpenOptions::new().write(true).open("/dev/tty") {
+        Ok(tty) => Ok(Box::new(tty)),
+        Err(_) if io::stderr().is_terminal() => Ok(Box::new(io::stderr())),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(not(unix))]
+fn open_terminal() -> io::Result<Box<dyn Write>> {
+    if io::stdout().is_terminal() {
+        Ok(Box::new(io::stdout()))
+    } else {
+        Err(io::Error::other("not attached to a terminal"))
+    }
+}
@@ -30,10 +30,15 @@
 //! # Ok::<(), tree_txt::TreeTxtError>(())
 //! ```
 
-use crate::error::Result;
+use crate::error::{ErrorPolicy, Result};
+use crate::line_range::{self, LineRange};
+use crate::ordering::SortOrder;
+use crate::tokens::SizeUnit;
+use crate::transform::{Normalization, TransformOverride, Transforms};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Main configuration structure for Tree-TXT operations.
 ///
@@ -41,7 +46,8 @@ use std::path::PathBuf;
 /// loaded from TOML files or created programmatically.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
-    /// List of files to include in the export (relative to base directory)
+    /// List of files to include in the export (relative to base directory).
+    /// An entry may end with line ranges, e.g. `src/lib.rs:120-240`.
     pub files: Vec<PathBuf>,
     /// Output formatting configuration
     #[serde(default)]
@@ -63,9 +69,91 @@ pub struct OutputFormat {
     /// Whether to add line numbers to file contents
     #[serde(default = "default_false")]
     pub include_line_numbers: bool,
+    /// Gutter written before each numbered line; `{n}` is replaced by the line
+    /// number, padded to the width of the file's largest line number
+    #[serde(default = "default_line_number_format")]
+    pub line_number_format: String,
+    /// Lines to export per file, keyed by the file's path as it appears in the
+    /// file list. Excerpts keep the original line numbers.
+    #[serde(default)]
+    pub line_ranges: BTreeMap<PathBuf, Vec<LineRange>>,
     /// Separator string used between sections
     #[serde(default = "default_separator")]
     pub file_separator: String,
+    /// Whether to annotate files with their size
+    #[serde(default = "default_false")]
+    pub show_file_size: bool,
+    /// Whether to annotate files with their line count
+    #[serde(default = "default_false")]
+    pub show_line_count: bool,
+    /// Whether to annotate files with their last-modified time
+    #[serde(default = "default_false")]
+    pub show_modified_time: bool,
+    /// Whether to annotate files with their detected language
+    #[serde(default = "default_false")]
+    pub show_language: bool,
+    /// Whether to annotate files with their permissions and executable bit
+    #[serde(default = "default_false")]
+    pub show_permissions: bool,
+    /// Whether to add a per-language and per-extension summary table to the header
+    #[serde(default = "default_false")]
+    pub include_summary: bool,
+    /// Whether to strip comments from file contents
+    #[serde(default = "default_false")]
+    pub strip_comments: bool,
+    /// Whether to collapse runs of blank lines into one
+    #[serde(default = "default_false")]
+    pub collapse_blank_lines: bool,
+    /// Whether to trim trailing whitespace from every line
+    #[serde(default = "default_false")]
+    pub trim_trailing_whitespace: bool,
+    /// Whether to convert CRLF and CR line endings to LF
+    #[serde(default = "default_false")]
+    pub normalize_line_endings: bool,
+    /// Expand tabs to spaces with tab stops every `tab_width` columns
+    #[serde(default)]
+    pub tab_width: Option<usize>,
+    /// Whether to remove a leading UTF-8 byte order mark
+    #[serde(default = "default_false")]
+    pub strip_bom: bool,
+    /// Per-extension transform overrides, keyed by extension (e.g. `"md"`)
+    #[serde(default)]
+    pub transform_overrides: BTreeMap<String, TransformOverride>,
+    /// Whether to redact secrets and credentials from file contents
+    #[serde(default = "default_false")]
+    pub redact_secrets: bool,
+    /// Whether redaction also replaces long random-looking tokens. Catches
+    /// unknown key formats, but also hashes, checksums and base64 data.
+    #[serde(default = "default_false")]
+    pub redact_high_entropy: bool,
+    /// Additional redaction patterns, mapping a kind name to a regular expression
+    #[serde(default)]
+    pub redaction_patterns: BTreeMap<String, String>,
+    /// Whether to reduce every supported source file to its outline
+    #[serde(default = "default_false")]
+    pub outline_mode: bool,
+    /// Files to reduce to their outline even when `outline_mode` is off
+    #[serde(default)]
+    pub outline_files: Vec<PathBuf>,
+    /// Maximum size of each output part; the export is split into
+    /// `<name>.part1.<ext>`, `<name>.part2.<ext>`, ... when it is exceeded
+    #[serde(default)]
+    pub max_part_size: Option<usize>,
+    /// Unit of `max_part_size` (`bytes` or `tokens`)
+    #[serde(default)]
+    pub part_size_unit: SizeUnit,
+    /// How to handle selected files that are missing or cannot be read
+    /// (`fail`, `warn` or `embed`)
+    #[serde(default)]
+    pub on_error: ErrorPolicy,
+    /// Order of the files in the contents section (`path`, `config`,
+    /// `dependencies`, `size`, `modified` or `entry-points`)
+    #[serde(default)]
+    pub sort_order: SortOrder,
+    /// Encodings to assume instead of detecting them, keyed by a glob matched
+    /// against paths relative to the project root (e.g. `"legacy/**/*.txt"`)
+    #[serde(default)]
+    pub encodings: BTreeMap<String, String>,
 }
 
 impl Default for OutputFormat {
@@ -74,11 +162,40 @@ impl Default for OutputFormat {
             include_tree: true,
             include_file_contents: true,
             include_line_numbers: false,
+            line_number_format: default_line_number_format(),
+            line_ranges: BTreeMap::new(),
             file_separator: "═".repeat(80),
+            show_file_size: false,
+            show_line_count: false,
+            show_modified_time: false,
+            show_language: false,
+            show_permissions: false,
+            include_summary: false,
+            strip_comments: false,
+            collapse_blank_lines: false,
+            trim_trailing_whitespace: false,
+            normalize_line_endings: false,
+            tab_width: None,
+            strip_bom: false,
+            transform_overrides: BTreeMap::new(),
+            redact_secrets: false,
+            redact_high_entropy: false,
+            redaction_patterns: BTreeMap::new(),
+            outline_mode: false,
+            outline_files: Vec::new(),
+            max_part_size: None,
+            part_size_unit: SizeUnit::Bytes,
+            on_error: ErrorPolicy::Warn,
+            sort_order: SortOrder::Path,
+            encodings: BTreeMap::new(),
         }
     }
 }
 
+fn default_line_number_format() -> String {
+    "{n} | ".to_string()
+}
+
 fn default_true() -> bool {
     true
 }
@@ -89,9 +206,81 @@ fn default_separator() -> String {
     "═".repeat(80)
 }
 
+impl OutputFormat {
+    /// Returns `true` if any per-file metadata annotation is enabled.
+    pub fn shows_metadata(&self) -> bool {
+        self.show_file_size
+            || self.show_line_count
+            || self.show_modified_time
+            || self.show_language
+            || self.show_permissions
+    }
+
+    /// Returns the globally configured content transforms.
+    pub fn transforms(&self) -> Transforms {
+        Transforms {
+            strip_comments: self.strip_comments,
+            collapse_blank_lines: self.collapse_blank_lines,
+            trim_trailing_whitespace: self.trim_trailing_whitespace,
+        }
+    }
+
+    /// Returns the configured whitespace normalization.
+    pub fn normalization(&self) -> Normalization {
+        Normalization {
+            line_endings: self.normalize_line_endings,
+            tab_width: self.tab_width,
+            strip_bom: self.strip_bom,
+        }
+    }
+
+    /// Returns the content transforms for `path`, taking per-extension
+    /// overrides into account.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tree_txt::OutputFormat;
+    /// use tree_txt::transform::TransformOverride;
+    /// use std::path::Path;
+    ///
+    /// let mut config = OutputFormat {
+    ///     strip_comments: true,
+    ///     ..Default::default()
+    /// };
+    /// config.transform_overrides.insert(
+    ///     "md".to_string(),
+    ///     TransformOverride {
+    ///         strip_comments: Some(false),
+    ///         ..Default::default()
+    ///     },
+    /// );
+    ///
+    /// assert!(config.transforms_for(Path::new("src/main.rs")).strip_comments);
+    /// assert!(!config.transforms_for(Path::new("README.md")).strip_comments);
+    /// ```
+    pub fn transforms_for(&self, path: &Path) -> Transforms {
+        let base = self.transforms();
+        let Some(extension) = path.extension() else {
+            return base;
+        };
+        let extension = extension.to_string_lossy();
+
+        self.transform_overrides
+            .iter()
+            .find(|(key, _)| key.trim_start_matches('.').eq_ignore_ascii_case(&extension))
+            .map_or(base, |(_, overrides)| overrides.apply_to(base))
+    }
+}
+
 impl Config {
     /// Loads configuration from a TOML file.
     ///
+    /// Relative paths in `files`, `outline_files` and `line_ranges` are resolved
+    /// against the directory containing the configuration file, so a config
+    /// behaves the same regardless of the directory Tree-TXT is started from.
+    /// Line ranges given in `files` entries are moved to `line_ranges`.
+    ///
     /// # Arguments
     ///
     /// * `path` - Path to the TOML configuration file
@@ -114,7 +303,171 @@ impl Config {
     /// - File paths in the configuration are malformed
     pub fn from_file(path: &str) -> Result<Self> {
         let content = fs::read_to_string(path)?;
-        let config: Config = toml::from_str(&content)?;
+        let mut config: Config = toml::from_str(&content)?;
+
+        if let Some(config_dir) = Path::new(path).parent() {
+            config.resolve_paths(config_dir);
+        }
+        config.split_line_ranges();
         Ok(config)
     }
+
+    /// Makes relative file paths relative to `dir` instead of the working directory.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tree_txt::Config;
+    /// use std::path::{Path, PathBuf};
+    ///
+    /// let mut config: Config = toml::from_str(r#"files = ["src/main.rs", "/etc/hosts"]"#).unwrap();
+    /// config.resolve_paths(Path::new("project"));
+    /// assert_eq!(
+    ///     config.files,
+    ///     vec![PathBuf::from("project/src/main.rs"), PathBuf::from("/etc/hosts")]
+    /// );
+    /// ```
+    pub fn resolve_paths(&mut self, dir: &Path) {
+        let resolve = |path: &mut PathBuf| {
+            if path.is_relative() {
+                *path = dir.join(&*path);
+            }
+        };
+        self.files.iter_mut().for_each(resolve);
+        self.output_format
+            .outline_files
+            .iter_mut()
+            .for_each(resolve);
+
+        let line_ranges = std::mem::take(&mut self.output_format.line_ranges);
+        for (mut path, ranges) in line_ranges {
+            resolve(&mut path);
+            self.output_format
+                .line_ranges
+                .entry(path)
+                .or_default()
+                .extend(ranges);
+        }
+    }
+
+    /// Moves line ranges written as `path:ranges` in `files` to
+    /// [`OutputFormat::line_ranges`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tree_txt::Config;
+    /// use tree_txt::line_range::LineRange;
+    /// use std::path::{Path, PathBuf};
+    ///
+    /// let mut config: Config = toml::from_str(r#"files = ["src/lib.rs:120-240"]"#).unwrap();
+    /// config.split_line_ranges();
+    /// assert_eq!(config.files, vec![PathBuf::from("src/lib.rs")]);
+    /// assert_eq!(
+    ///     config.output_format.line_ranges[Path::new("src/lib.rs")],
+    ///     vec![LineRange::new(120, Some(240))]
+    /// );
+    /// ```
+    pub fn split_line_ranges(&mut self) {
+        let mut files = Vec::with_capacity(self.files.len());
+        for spec in &self.files {
+            let (path, ranges) = line_range::split_file_spec(spec);
+            if !ranges.is_empty() {
+                self.output_format
+                    .line_ranges
+                    .entry(path.clone())
+                    .or_default()
+                    .extend(ranges);
+            }
+            if !files.contains(&path) {
+                files.push(path);
+            }
+        }
+        self.files = files;
+    }
+
+    /// Renders a commented starter configuration file listing `files`.
+    ///
+    /// Every output option is included with its default value, commented out,
+    /// so the file documents what can be customized.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tree_txt::Config;
+    /// use std::path::PathBuf;
+    ///
+    /// let scaffold = Config::scaffold(&[PathBuf::from("src/main.rs")]);
+    /// let config: Config = toml::from_str(&scaffold).unwrap();
+    /// assert_eq!(config.files, vec![PathBuf::from("src/main.rs")]);
+    /// assert!(config.output_format.include_tree);
+    /// ```
+    pub fn scaffold(files: &[PathBuf]) -> String {
+        let mut content = String::from("# Tree-TXT configuration\n");
+        content.push_str("# Export with: tree-txt export --config <this file>\n\n");
+
+        if files.is_empty() {
+            content.push_str("files = [\n    # \"src/main.rs\",\n]\n");
+        } else {
+            content.push_str("files = [\n");
+            for file in files {
+                let path = toml::Value::String(file.to_string_lossy().into_owned());
+                content.push_str(&format!("    {path},\n"));
+            }
+            content.push_str("]\n");
+        }
+
+        content.push_str(SCAFFOLD_OUTPUT_FORMAT);
+        content
+    }
 }
+
+/// Output format section of [`Config::scaffold`], with default values.
+const SCAFFOLD_OUTPUT_FORMAT: &str = r#"
+[output_format]
+# include_tree = true
+# include_file_contents = true
+# include_line_numbers = false
+# line_number_format = "{n} | "
+
+# Metadata annotations
+# show_file_size = false
+# show_line_count = false
+# show_modified_time = false
+# show_language = false
+# show_permissions = false
+# include_summary = false
+
+# Content transforms
+# strip_comments = false
+# collapse_blank_lines = false
+# trim_trailing_whitespace = false
+
+# Whitespace normalization
+# normalize_line_endings = false
+# tab_width = 4
+# strip_bom = false
+
+# Outline export (signatures only)
+# outline_mode = false
+# outline_files = []
+
+# Split into parts of at most this size ("bytes" or "tokens")
+# max_part_size = 100000
+# part_size_unit = "bytes"
+
+# Secret redaction (also of high-entropy tokens such as random API keys)
+# redact_secrets = false
+# redact_high_entropy = false
+
+# Missing or unreadable files: "fail", "warn" (skip) or "embed" the error
+# on_error = "warn"
+
+# Order of file contents: "path", "config", "dependencies", "size",
+# "modified" or "entry-points"
+# sort_order = "path"
+
+# Encodings are detected automatically; force one for matching files
+# [output_format.encodings]
+# "legacy/**/*.txt" = "windows-1252"
+"#;
@@ -0,0 +1,447 @@
+//! # Dependency Analysis
+//!
+//! Finds the files a source file imports, so exports can list dependencies
+//! before the files that use them and selections can pull in the modules
+//! they need.
+//!
+//! Imports are found with lightweight pattern matching rather than a full
+//! parser, and only imports that resolve to existing files are reported.
+//! Supported are:
+//! - Rust `mod name;` declarations and `use crate::`, `use super::` and
+//!   `use self::` paths
+//! - JavaScript and TypeScript `import`, `export ... from` and `require()` of
+//!   relative paths (`./` or `../`)
+//! - Python `import` and `from ... import`, both relative and absolute
+//!   (resolved against the directories above the importing file)
+//!
+//! # Examples
+//!
+//! ```rust
+//! use tree_txt::dependencies::{dependency_order, imports};
+//!
+//! let dir = std::env::temp_dir().join(format!("tree-txt-deps-doc-{}", std::process::id()));
+//! std::fs::create_dir_all(dir.join("src"))?;
+//! std::fs::write(dir.join("Cargo.toml"), "")?;
+//! let main = dir.join("src/main.rs");
+//! let config = dir.join("src/config.rs");
+//! std::fs::write(&main, "mod config;\nuse crate::config::Config;\nfn main() {}\n")?;
+//! std::fs::write(&config, "pub struct Config;\n")?;
+//!
+//! let main_source = std::fs::read_to_string(&main)?;
+//! assert_eq!(imports(&main, &main_source), vec![config.clone()]);
+//!
+//! let files = vec![main.clone(), config.clone()];
+//! let order = dependency_order(&files, |path| std::fs::read_to_string(path).ok());
+//! assert_eq!(order, vec![config, main]);
+//!
+//! std::fs::remove_dir_all(&dir)?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::language::Language;
+use crate::project::{ROOT_MARKERS, Workspace};
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+static RUST_MOD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+([A-Za-z_][A-Za-z0-9_]*)\s*;")
+        .expect("valid mod pattern")
+});
+
+static RUST_USE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\buse\s+(crate|super|self)((?:::[A-Za-z_][A-Za-z0-9_]*)*)(?:::\{([^}]*)\})?")
+        .expect("valid use pattern")
+});
+
+static JS_IMPORT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?:\bimport\s*(?:[^'"();]*?\bfrom\s*)?|\bexport\s[^'";]*?\bfrom\s*|\b(?:require|import)\s*\(\s*)['"](\.{1,2}/[^'"]*)['"]"#,
+    )
+    .expect("valid import pattern")
+});
+
+static PYTHON_IMPORT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^[ \t]*import[ \t]+([\w.]+(?:[ \t]+as[ \t]+\w+)?(?:[ \t]*,[ \t]*[\w.]+(?:[ \t]+as[ \t]+\w+)?)*)")
+        .expect("valid import pattern")
+});
+
+static PYTHON_FROM: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^[ \t]*from[ \t]+(\.*)([\w.]*)[ \t]+import[ \t]*(?:\(([^)]*)\)|([\w \t,*]+))")
+        .expect("valid from-import pattern")
+});
+
+/// Extensions tried when resolving an extensionless JavaScript or TypeScript import.
+const JS_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs", "mts", "cts"];
+
+/// A file added to a selection because a selected file imports it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Added {
+    /// The imported file
+    pub path: PathBuf,
+    /// The file whose import pulled it in
+    pub imported_by: PathBuf,
+    /// Number of import steps from the original selection (1 = direct import)
+    pub depth: usize,
+}
+
+/// Follows the imports of `selection` up to `max_depth` steps and returns
+/// the files that are not selected yet, in the order they were found.
+///
+/// Imports outside the roots of `workspace` (for example a sibling checkout
+/// reached through `../`) are neither added nor followed.
+///
+/// # Examples
+///
+/// ```rust
+/// use tree_txt::dependencies::follow;
+/// use tree_txt::project::Workspace;
+///
+/// let dir = std::env::temp_dir().join(format!("tree-txt-follow-doc-{}", std::process::id()));
+/// std::fs::create_dir_all(dir.join("app"))?;
+/// std::fs::write(dir.join("app/main.py"), "from models import User\n")?;
+/// std::fs::write(dir.join("app/models.py"), "from . import db\n")?;
+/// std::fs::write(dir.join("app/db.py"), "")?;
+///
+/// let workspace = Workspace::single(&dir);
+/// let direct = follow(&workspace, &[dir.join("app/main.py")], 1);
+/// assert_eq!(direct.len(), 1);
+/// assert_eq!(direct[0].path, dir.join("app/models.py"));
+/// assert_eq!(direct[0].imported_by, dir.join("app/main.py"));
+///
+/// let all = follow(&workspace, &[dir.join("app/main.py")], 2);
+/// assert_eq!(all[1].path, dir.join("app/db.py"));
+/// assert_eq!(all[1].depth, 2);
+///
+/// // Imports outside the workspace roots are skipped
+/// std::fs::write(dir.join("shared.js"), "")?;
+/// std::fs::write(dir.join("app/index.js"), "import { log } from '../shared.js';\n")?;
+/// let index = [dir.join("app/index.js")];
+/// assert_eq!(follow(&workspace, &index, 1)[0].path, dir.join("shared.js"));
+/// assert!(follow(&Workspace::single(&dir.join("app")), &index, 1).is_empty());
+///
+/// std::fs::remove_dir_all(&dir)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn follow(workspace: &Workspace, selection: &[PathBuf], max_depth: usize) -> Vec<Added> {
+    let mut known: HashSet<PathBuf> = selection.iter().cloned().collect();
+    let mut queue: VecDeque<(PathBuf, usize)> =
+        selection.iter().map(|path| (path.clone(), 0)).collect();
+    let mut added = Vec::new();
+
+    while let Some((path, depth)) = queue.pop_front() {
+        if depth >= max_depth {
+            continue;
+        }
+        let Ok(source) = fs::read_to_string(&path) else {
+            continue;
+        };
+
+        for import in imports(&path, &source) {
+            if workspace.root_of(&import).is_some() && known.insert(import.clone()) {
+                queue.push_back((import.clone(), depth + 1));
+                added.push(Added {
+                    path: import,
+                    imported_by: path.clone(),
+                    depth: depth + 1,
+                });
+            }
+        }
+    }
+
+    added
+}
+
+/// Returns the existing files that `path` imports, in the order they are
+/// first mentioned in `source`.
+pub fn imports(path: &Path, source: &str) -> Vec<PathBuf> {
+    let found = match Language::detect(path) {
+        Language::Rust => rust_imports(path, source),
+        Language::JavaScript | Language::TypeScript => js_imports(path, source),
+        Language::Python => python_imports(path, source),
+        _ => Vec::new(),
+    };
+
+    let mut seen = HashSet::new();
+    found
+        .into_iter()
+        .filter(|import| import != path && seen.insert(import.clone()))
+        .collect()
+}
+
+/// Orders `files` so that every file comes after the files it imports.
+///
+/// `source` returns the contents of a file. Files without dependencies
+/// between them keep their relative order in `files`, and files in import
+/// cycles are emitted in that order once nothing else is ready.
+pub fn dependency_order<S: AsRef<str>>(
+    files: &[PathBuf],
+    source: impl Fn(&Path) -> Option<S>,
+) -> Vec<PathBuf> {
+    let index: HashMap<&Path, usize> = files
+        .iter()
+        .enumerate()
+        .map(|(position, path)| (path.as_path(), position))
+        .collect();
+
+    // dependencies[i] = files among `files` that file i imports
+    let dependencies: Vec<HashSet<usize>> = files
+        .iter()
+        .map(|path| {
+            source(path)
+                .map(|source| {
+                    imports(path, source.as_ref())
+                        .iter()
+                        .filter_map(|import| index.get(import.as_path()).copied())
+                        .collect()
+                })
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); files.len()];
+    for (file, imported) in dependencies.iter().enumerate() {
+        for &dependency in imported {
+            dependents[dependency].push(file);
+        }
+    }
+
+    let mut waiting_on: Vec<usize> = dependencies.iter().map(HashSet::len).collect();
+    let mut ready: BTreeSet<usize> = (0..files.len()).filter(|&i| waiting_on[i] == 0).collect();
+    let mut remaining: BTreeSet<usize> = (0..files.len()).collect();
+    let mut order = Vec::with_capacity(files.len());
+
+    while !remaining.is_empty() {
+        // Break cycles by taking the earliest remaining file
+        let next = ready
+            .pop_first()
+            .or_else(|| remaining.first().copied())
+            .expect("remaining is not empty");
+        if !remaining.remove(&next) {
+            continue;
+        }
+        order.push(files[next].clone());
+
+        for &dependent in &dependents[next] {
+            waiting_on[dependent] = waiting_on[dependent].saturating_sub(1);
+            if waiting_on[dependent] == 0 && remaining.contains(&dependent) {
+                ready.insert(dependent);
+            }
+        }
+    }
+
+    order
+}
+
+fn rust_imports(path: &Path, source: &str) -> Vec<PathBuf> {
+    let module_dir = rust_module_dir(path);
+    let mut found = Vec::new();
+
+    for captures in RUST_MOD.captures_iter(source) {
+        let name = &captures[1];
+        found.extend(resolve_rust_module(&module_dir, &[name]));
+    }
+
+    for captures in RUST_USE.captures_iter(source) {
+        let base = match &captures[1] {
+            "crate" => rust_crate_root(path),
+            "super" => module_dir.parent().map(Path::to_path_buf),
+            _ => Some(module_dir.clone()),
+        };
+        let Some(base) = base else {
+            continue;
+        };
+
+        let prefix: Vec<&str> = captures[2].split("::").filter(|s| !s.is_empty()).collect();
+        match captures.get(3) {
+            Some(group) => {
+                for item in group.as_str().split(',') {
+                    let mut segments = prefix.clone();
+                    segments.extend(
+                        item.split("::")
+                            .map(str::trim)
+                            .take_while(|s| !s.is_empty() && !s.contains(' ')),
+                    );
+                    found.extend(resolve_rust_module(&base, &segments));
+                }
+            }
+            None => found.extend(resolve_rust_module(&base, &prefix)),
+        }
+    }
+
+    found
+}
+
+/// Returns the directory holding the submodules of the module in `path`.
+fn rust_module_dir(path: &Path) -> PathBuf {
+    let parent = path.parent().unwrap_or(Path::new("")).to_path_buf();
+    match path.file_name().and_then(|name| name.to_str()) {
+        Some("lib.rs" | "main.rs" | "mod.rs") => parent,
+        _ => parent.join(path.file_stem().unwrap_or_default()),
+    }
+}
+
+/// Returns the `src` directory of the crate containing `path`.
+fn rust_crate_root(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .skip(1)
+        .find(|dir| dir.join("Cargo.toml").is_file())
+        .map(|dir| dir.join("src"))
+}
+
+/// Resolves the longest prefix of `segments` that names a module file below `base`.
+fn resolve_rust_module(base: &Path, segments: &[&str]) -> Option<PathBuf> {
+    (1..=segments.len()).rev().find_map(|len| {
+        let dir: PathBuf = segments[..len - 1].iter().collect();
+        let name = segments[len - 1];
+        [
+            base.join(&dir).join(format!("{name}.rs")),
+            base.join(&dir).join(name).join("mod.rs"),
+        ]
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+    })
+}
+
+fn js_imports(path: &Path, source: &str) -> Vec<PathBuf> {
+    let dir = path.parent().unwrap_or(Path::new(""));
+    JS_IMPORT
+        .captures_iter(source)
+        .filter_map(|captures| resolve_js_module(&dir.join(&captures[1])))
+        .collect()
+}
+
+/// Resolves an import specifier the way bundlers do: the exact file, the file
+/// with a known extension, TypeScript sources for `.js` specifiers, or an
+/// `index` file in a directory.
+fn resolve_js_module(target: &Path) -> Option<PathBuf> {
+    if target.is_file() {
+        return Some(normalize(target));
+    }
+
+    let mut candidates: Vec<PathBuf> = JS_EXTENSIONS
+        .iter()
+        .map(|extension| append_extension(target, extension))
+        .collect();
+    if matches!(
+        target.extension().and_then(|e| e.to_str()),
+        Some("js" | "jsx" | "mjs" | "cjs")
+    ) {
+        candidates.push(target.with_extension("ts"));
+        candidates.push(target.with_extension("tsx"));
+    }
+    candidates.extend(
+        JS_EXTENSIONS
+            .iter()
+            .map(|extension| target.join(format!("index.{extension}"))),
+    );
+
+    candidates
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+        .map(|candidate| normalize(&candidate))
+}
+
+fn append_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
+
+/// Removes `.` and `..` components, so imports like `../lib/util` match the
+/// canonical paths used for selected files.
+fn normalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn python_imports(path: &Path, source: &str) -> Vec<PathBuf> {
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let search_dirs = python_search_dirs(dir);
+    let resolve_absolute = |module: &[&str]| {
+        search_dirs
+            .iter()
+            .find_map(|base| resolve_python_module(base, module))
+    };
+
+    let mut found = Vec::new();
+    for captures in PYTHON_IMPORT.captures_iter(source) {
+        for item in captures[1].split(',') {
+            let module = item.split_whitespace().next().unwrap_or_default();
+            let segments: Vec<&str> = module.split('.').collect();
+            found.extend(resolve_absolute(&segments));
+        }
+    }
+
+    for captures in PYTHON_FROM.captures_iter(source) {
+        let dots = captures[1].len();
+        let module: Vec<&str> = captures[2].split('.').filter(|s| !s.is_empty()).collect();
+        let names = captures
+            .get(3)
+            .or_else(|| captures.get(4))
+            .map_or("", |names| names.as_str())
+            .split(',')
+            .filter_map(|item| item.split_whitespace().next())
+            .filter(|name| *name != "*");
+
+        let base = if dots > 0 {
+            dir.ancestors().nth(dots - 1).map(Path::to_path_buf)
+        } else {
+            None
+        };
+        let resolve = |segments: &[&str]| match &base {
+            Some(base) => resolve_python_module(base, segments),
+            None => resolve_absolute(segments),
+        };
+
+        // `from package import name` may import a submodule or a plain name
+        let mut resolved_any = false;
+        for name in names {
+            let mut segments = module.clone();
+            segments.push(name);
+            if let Some(submodule) = resolve(&segments) {
+                found.push(submodule);
+                resolved_any = true;
+            }
+        }
+        if !resolved_any && !module.is_empty() {
+            found.extend(resolve(&module));
+        }
+    }
+
+    found
+}
+
+/// Returns the directories absolute Python imports are resolved against: the
+/// importing file's directory and its ancestors up to the project root, with
+/// the root's `src` directory as well.
+fn python_search_dirs(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    for ancestor in dir.ancestors() {
+        dirs.push(ancestor.to_path_buf());
+        let is_root = ROOT_MARKERS
+            .iter()
+            .chain(&["pyproject.toml", "setup.py"])
+            .any(|marker| ancestor.join(marker).exists());
+        if is_root {
+            dirs.push(ancestor.join("src"));
+            break;
+        }
+    }
+    dirs
+}
+
+fn resolve_python_module(base: &Path, segments: &[&str]) -> Option<PathBuf> {
+    if segments.is_empty() {
+        return None;
+    }
+    let module: PathBuf = segments.iter().collect();
+    [
+        base.join(&module).with_extension("py"),
+        base.join(&module).join("__init__.py"),
+    ]
+    .into_iter()
+    .find(|candidate| candidate.is_file())
+}
@@ -0,0 +1,290 @@
+//! # Text Encodings
+//!
+//! Detects the encoding of exported files and transcodes them to UTF-8.
+//!
+//! Detection looks, in order, for:
+//! - a byte order mark (UTF-8, UTF-16LE or UTF-16BE)
+//! - UTF-16 without a byte order mark, recognised by its pattern of NUL bytes
+//! - valid UTF-8
+//! - text that is plausible as Windows-1252: no control characters other
+//!   than whitespace, none of the five bytes Windows-1252 leaves undefined,
+//!   and mostly ASCII
+//!
+//! Windows-1252 is only a guess, which the file banner says. Files matching
+//! none of the above are treated as binary and rejected. Detection can be
+//! overridden per glob with [`EncodingOverrides`].
+//!
+//! # Examples
+//!
+//! ```rust
+//! use tree_txt::encoding;
+//!
+//! let decoded = encoding::decode(b"caf\xe9\n", None)?;
+//! assert_eq!(decoded.text, "café\n");
+//! assert_eq!(
+//!     decoded.banner_note().as_deref(),
+//!     Some("windows-1252 (guessed, transcoded to UTF-8)")
+//! );
+//!
+//! let decoded = encoding::decode(b"\xff\xfeh\x00i\x00", None)?;
+//! assert_eq!(decoded.text, "hi");
+//! assert_eq!(decoded.encoding, encoding_rs::UTF_16LE);
+//!
+//! assert!(encoding::decode(b"\x7fELF\x02\x01\x01\x00\x00\xff", None).is_err());
+//! assert!(encoding::decode(b"\x89PNG\r\n\x1a\n\x9d\xe2", None).is_err());
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::error::{Result, TreeTxtError};
+use encoding_rs::{Encoding, UTF_8, UTF_16BE, UTF_16LE, WINDOWS_1252};
+use globset::{Glob, GlobMatcher};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+/// Number of leading bytes inspected when looking for UTF-16 without a BOM.
+const SNIFF_LEN: usize = 4096;
+
+/// Bytes that Windows-1252 leaves undefined.
+const UNDEFINED_WINDOWS_1252: [u8; 5] = [0x81, 0x8d, 0x8f, 0x90, 0x9d];
+
+/// Highest share of non-ASCII bytes, in percent, in text guessed to be
+/// Windows-1252. Western European prose stays far below it.
+const MAX_NON_ASCII_PERCENT: usize = 30;
+
+/// A file's contents transcoded to UTF-8.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoded {
+    /// The contents as UTF-8. A UTF-8 byte order mark is kept as `U+FEFF`.
+    pub text: String,
+    /// Encoding the file was read as
+    pub encoding: &'static Encoding,
+    /// Whether the file started with a byte order mark
+    pub bom: bool,
+    /// Whether the encoding is a fallback guess rather than detected or forced
+    pub guessed: bool,
+}
+
+impl Decoded {
+    /// Returns `true` if the file was not already UTF-8.
+    pub fn is_transcoded(&self) -> bool {
+        self.encoding != UTF_8
+    }
+
+    /// Describes the original encoding for the file banner, or `None` for
+    /// plain UTF-8.
+    pub fn banner_note(&self) -> Option<String> {
+        let bom = if self.bom { " with BOM" } else { "" };
+        let guessed = if self.guessed { "guessed, " } else { "" };
+        if self.is_transcoded() {
+            Some(format!(
+                "{}{bom} ({guessed}transcoded to UTF-8)",
+                self.encoding.name()
+            ))
+        } else if self.bom {
+            Some(format!("{}{bom}", self.encoding.name()))
+        } else {
+            None
+        }
+    }
+}
+
+/// Decodes `bytes` to UTF-8, detecting their encoding unless `forced` is given.
+///
+/// A byte order mark takes precedence over `forced`. Bytes that are invalid in
+/// a forced encoding are replaced with `U+FFFD`.
+///
+/// # Errors
+///
+/// Returns an [`io::ErrorKind::InvalidData`] error if the contents look binary
+/// (neither UTF-8, UTF-16 nor plausible Windows-1252) or do not match the
+/// encoding announced by their byte order mark.
+pub fn decode(bytes: &[u8], forced: Option<&'static Encoding>) -> io::Result<Decoded> {
+    if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+        // UTF-8 files are exported as-is, so their BOM stays in the text
+        let body = if encoding == UTF_8 {
+            bytes
+        } else {
+            &bytes[bom_len..]
+        };
+        let text = decode_strict(encoding, body).ok_or_else(|| {
+            invalid_data(format!(
+                "invalid {} data after byte order mark",
+                encoding.name()
+            ))
+        })?;
+        return Ok(Decoded {
+            text,
+            encoding,
+            bom: true,
+            guessed: false,
+        });
+    }
+
+    if let Some(encoding) = forced {
+        let (text, _) = encoding.decode_without_bom_handling(bytes);
+        return Ok(Decoded {
+            text: text.into_owned(),
+            encoding,
+            bom: false,
+            guessed: false,
+        });
+    }
+
+    // ASCII in UTF-16 is also valid UTF-8, so check for it first
+    if let Some(encoding) = sniff_utf16(bytes)
+        && let Some(text) = decode_strict(encoding, bytes)
+    {
+        return Ok(Decoded {
+            text,
+            encoding,
+            bom: false,
+            guessed: false,
+        });
+    }
+
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return Ok(Decoded {
+            text: text.to_string(),
+            encoding: UTF_8,
+            bom: false,
+            guessed: false,
+        });
+    }
+
+    if !plausible_windows_1252(bytes) {
+        return Err(invalid_data("file appears to be binary".to_string()));
+    }
+
+    let (text, _) = WINDOWS_1252.decode_without_bom_handling(bytes);
+    Ok(Decoded {
+        text: text.into_owned(),
+        encoding: WINDOWS_1252,
+        bom: false,
+        guessed: true,
+    })
+}
+
+/// Returns `true` if `bytes` look like Windows-1252 text rather than binary
+/// data that happens not to be UTF-8.
+fn plausible_windows_1252(bytes: &[u8]) -> bool {
+    let control = |byte: u8| {
+        (byte < 0x20 && !matches!(byte, b'\t' | b'\n' | b'\r' | 0x0c | 0x1b)) || byte == 0x7f
+    };
+    if bytes
+        .iter()
+        .any(|&byte| control(byte) || UNDEFINED_WINDOWS_1252.contains(&byte))
+    {
+        return false;
+    }
+
+    let non_ascii = bytes.iter().filter(|byte| !byte.is_ascii()).count();
+    non_ascii * 100 <= bytes.len() * MAX_NON_ASCII_PERCENT
+}
+
+/// Recognises UTF-16 without a BOM: mostly-ASCII text in UTF-16 has a NUL in
+/// nearly every high byte and almost none in the low bytes.
+fn sniff_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    let sample = &bytes[..bytes.len().min(SNIFF_LEN)];
+    if !bytes.len().is_multiple_of(2) || sample.len() < 2 {
+        return None;
+    }
+
+    let pairs = sample.len() / 2;
+    let (mut even_nuls, mut odd_nuls) = (0, 0);
+    for pair in sample.chunks_exact(2) {
+        even_nuls += usize::from(pair[0] == 0);
+        odd_nuls += usize::from(pair[1] == 0);
+    }
+
+    let mostly = |count: usize| count * 10 >= pairs * 4;
+    let rarely = |count: usize| count * 10 <= pairs;
+    if mostly(odd_nuls) && rarely(even_nuls) {
+        Some(UTF_16LE)
+    } else if mostly(even_nuls) && rarely(odd_nuls) {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+fn decode_strict(encoding: &'static Encoding, bytes: &[u8]) -> Option<String> {
+    encoding
+        .decode_without_bom_handling_and_without_replacement(bytes)
+        .map(|text| text.into_owned())
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Encodings forced for files matching configured glob patterns.
+///
+/// Patterns are matched against paths relative to their project root. When
+/// several patterns match, the longest one wins.
+///
+/// # Examples
+///
+/// ```rust
+/// use tree_txt::encoding::EncodingOverrides;
+/// use std::collections::BTreeMap;
+/// use std::path::Path;
+///
+/// let mut config = BTreeMap::new();
+/// config.insert("legacy/**/*.txt".to_string(), "windows-1252".to_string());
+/// config.insert("legacy/utf16/*.txt".to_string(), "utf-16le".to_string());
+///
+/// let overrides = EncodingOverrides::new(&config)?;
+/// assert_eq!(
+///     overrides.encoding_for(Path::new("legacy/readme.txt")),
+///     Some(encoding_rs::WINDOWS_1252)
+/// );
+/// assert_eq!(
+///     overrides.encoding_for(Path::new("legacy/utf16/notes.txt")),
+///     Some(encoding_rs::UTF_16LE)
+/// );
+/// assert_eq!(overrides.encoding_for(Path::new("src/main.rs")), None);
+/// # Ok::<(), tree_txt::TreeTxtError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EncodingOverrides {
+    overrides: Vec<(GlobMatcher, &'static Encoding)>,
+}
+
+impl EncodingOverrides {
+    /// Compiles a map of glob patterns to encoding labels such as
+    /// `windows-1252`, `latin1` or `utf-16le`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TreeTxtError::ConfigError`] for an invalid pattern or an
+    /// unknown encoding label.
+    pub fn new(overrides: &BTreeMap<String, String>) -> Result<Self> {
+        let mut compiled = Vec::with_capacity(overrides.len());
+        for (pattern, label) in overrides {
+            let glob = Glob::new(pattern).map_err(|err| {
+                TreeTxtError::ConfigError(format!("Invalid encoding glob '{pattern}': {err}"))
+            })?;
+            let encoding = Encoding::for_label(label.trim().as_bytes()).ok_or_else(|| {
+                TreeTxtError::ConfigError(format!(
+                    "Unknown encoding '{label}' for files matching '{pattern}'"
+                ))
+            })?;
+            compiled.push((glob.compile_matcher(), encoding));
+        }
+
+        // Longest pattern first, so more specific globs win
+        compiled.sort_by_key(|(matcher, _)| std::cmp::Reverse(matcher.glob().glob().len()));
+        Ok(Self {
+            overrides: compiled,
+        })
+    }
+
+    /// Returns the encoding forced for `path`, if any pattern matches it.
+    pub fn encoding_for(&self, path: &Path) -> Option<&'static Encoding> {
+        self.overrides
+            .iter()
+            .find(|(matcher, _)| matcher.is_match(path))
+            .map(|(_, encoding)| *encoding)
+    }
+}
@@ -1,5 +1,69 @@
+//! # Error Handling
+//!
+//! [`TreeTxtError`] is the error type returned throughout the library. Variants
+//! carry the path and operation that failed so callers can react to specific
+//! failures, and [`TreeTxtError::exit_code`] maps each variant to the exit
+//! status used by the `tree-txt` binary.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use tree_txt::{OutputFormat, OutputGenerator, TreeTxtError};
+//! use std::path::{Path, PathBuf};
+//!
+//! let files = vec![PathBuf::from("Cargo.toml")];
+//! let result = OutputGenerator::new().generate_with_config(
+//!     Path::new("."),
+//!     &files,
+//!     "no-such-directory/export.txt",
+//!     &OutputFormat::default(),
+//! );
+//!
+//! match result {
+//!     Err(TreeTxtError::WriteFile { path, .. }) => {
+//!         assert_eq!(path, PathBuf::from("no-such-directory/export.txt"));
+//!     }
+//!     other => panic!("unexpected result: {other:?}"),
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::path::PathBuf;
+
+/// What to do when a selected file cannot be exported because it is missing,
+/// unreadable or cannot be decoded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorPolicy {
+    /// Abort the export with an error
+    Fail,
+    /// Leave the file out of the export
+    #[default]
+    Warn,
+    /// Write the error into the file's section of the export
+    Embed,
+}
 
+/// Errors returned by Tree-TXT operations.
+///
+/// Each variant has a distinct exit code (see [`exit_code`](Self::exit_code)):
+///
+/// | Code | Variant |
+/// |------|---------|
+/// | 1 | [`Io`](Self::Io), [`TomlSer`](Self::TomlSer) |
+/// | 3 | [`ConfigError`](Self::ConfigError), [`Toml`](Self::Toml), [`InvalidPattern`](Self::InvalidPattern) |
+/// | 4 | [`InvalidPath`](Self::InvalidPath) |
+/// | 5 | [`NoFilesSelected`](Self::NoFilesSelected) |
+/// | 6 | [`PermissionDenied`](Self::PermissionDenied) |
+/// | 7 | [`ReadFile`](Self::ReadFile), [`ReadDir`](Self::ReadDir) |
+/// | 8 | [`WriteFile`](Self::WriteFile) |
+/// | 9 | [`StateFile`](Self::StateFile) |
+/// | 10 | [`Terminal`](Self::Terminal) |
+/// | 11 | [`ClipboardUnavailable`](Self::ClipboardUnavailable) |
+/// | 130 | [`Cancelled`](Self::Cancelled) |
+///
+/// Exit code 2 is reserved for command-line usage errors.
 #[derive(Debug)]
 pub enum TreeTxtError {
     Io(std::io::Error),
@@ -9,6 +73,89 @@ pub enum TreeTxtError {
     NoFilesSelected,
     PermissionDenied(String),
     ConfigError(String),
+    ClipboardUnavailable(String),
+    /// A selected file could not be read
+    ReadFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// A directory could not be listed
+    ReadDir {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// An output file could not be written
+    WriteFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The saved-selection state file could not be loaded or saved
+    StateFile {
+        path: PathBuf,
+        /// What was being done, e.g. `"read"` or `"parse"`
+        operation: &'static str,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    /// The terminal could not be set up, drawn to or restored
+    Terminal {
+        /// What was being done, e.g. `"enable raw mode"`
+        operation: &'static str,
+        source: std::io::Error,
+    },
+    /// A custom redaction pattern or a search query is not a valid regular expression
+    InvalidPattern(regex::Error),
+    /// The user cancelled the interactive selector with Ctrl+C, or it was
+    /// interrupted by SIGINT or SIGTERM
+    Cancelled,
+}
+
+impl TreeTxtError {
+    /// Returns the process exit code for this error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tree_txt::TreeTxtError;
+    ///
+    /// assert_eq!(TreeTxtError::NoFilesSelected.exit_code(), 5);
+    /// ```
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::Io(_) | Self::TomlSer(_) => 1,
+            Self::ConfigError(_) | Self::Toml(_) | Self::InvalidPattern(_) => 3,
+            Self::InvalidPath(_) => 4,
+            Self::NoFilesSelected => 5,
+            Self::PermissionDenied(_) => 6,
+            Self::ReadFile { .. } | Self::ReadDir { .. } => 7,
+            Self::WriteFile { .. } => 8,
+            Self::StateFile { .. } => 9,
+            Self::Terminal { .. } => 10,
+            Self::ClipboardUnavailable(_) => 11,
+            // Shells report processes killed by SIGINT as 128 + 2
+            Self::Cancelled => 130,
+        }
+    }
+
+    /// Wraps a terminal I/O failure with the operation that caused it.
+    pub(crate) fn terminal(operation: &'static str) -> impl FnOnce(std::io::Error) -> Self {
+        move |source| Self::Terminal { operation, source }
+    }
+
+    /// Wraps a state file failure with the file and operation that caused it.
+    pub(crate) fn state_file<E>(
+        path: &std::path::Path,
+        operation: &'static str,
+    ) -> impl FnOnce(E) -> Self
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let path = path.to_path_buf();
+        move |source| Self::StateFile {
+            path,
+            operation,
+            source: Box::new(source),
+        }
+    }
 }
 
 impl fmt::Display for TreeTxtError {
@@ -21,6 +168,34 @@ impl fmt::Display for TreeTxtError {
             Self::NoFilesSelected => write!(f, "No files were selected for export"),
             Self::PermissionDenied(path) => write!(f, "Permission denied accessing: {path}"),
             Self::ConfigError(msg) => write!(f, "Configuration error: {msg}"),
+            Self::ClipboardUnavailable(msg) => write!(f, "Clipboard unavailable: {msg}"),
+            Self::ReadFile { path, source } => {
+                write!(f, "Cannot read file '{}': {source}", path.display())
+            }
+            Self::ReadDir { path, source } => {
+                write!(f, "Cannot read directory '{}': {source}", path.display())
+            }
+            Self::WriteFile { path, source } => {
+                write!(
+                    f,
+                    "Cannot write to output file '{}': {source}",
+                    path.display()
+                )
+            }
+            Self::StateFile {
+                path,
+                operation,
+                source,
+            } => write!(
+                f,
+                "Cannot {operation} state file '{}': {source}",
+                path.display()
+            ),
+            Self::Terminal { operation, source } => {
+                write!(f, "Terminal error (failed to {operation}): {source}")
+            }
+            Self::InvalidPattern(err) => write!(f, "Invalid regular expression: {err}"),
+            Self::Cancelled => write!(f, "Cancelled"),
         }
     }
 }
@@ -31,6 +206,12 @@ impl std::error::Error for TreeTxtError {
             Self::Io(err) => Some(err),
             Self::Toml(err) => Some(err),
             Self::TomlSer(err) => Some(err),
+            Self::ReadFile { source, .. }
+            | Self::ReadDir { source, .. }
+            | Self::WriteFile { source, .. }
+            | Self::Terminal { source, .. } => Some(source),
+            Self::StateFile { source, .. } => Some(source.as_ref()),
+            Self::InvalidPattern(err) => Some(err),
             _ => None,
         }
     }
@@ -57,4 +238,10 @@ impl From<toml::ser::Error> for TreeTxtError {
     }
 }
 
+impl From<regex::Error> for TreeTxtError {
+    fn from(err: regex::Error) -> Self {
+        Self::InvalidPattern(err)
+    }
+}
+
 pub type Result<T> = std::result::Result<T, TreeTxtError>;
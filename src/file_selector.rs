@@ -1,15 +1,67 @@
-use anyhow::Result;
-use ratatui::crossterm::{
-    ExecutableCommand,
-    event::{Event, KeyCode, KeyEventKind, KeyModifiers, read},
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+use crate::dependencies;
+use crate::error::{Result, TreeTxtError};
+use crate::history::History;
+use crate::keymap::{Action, KeyBinding, Keymap};
+use crate::line_range::LineRange;
+use crate::project::Workspace;
+use crate::redaction::is_sensitive_filename;
+use crate::search::{self, FileMatch, Query};
+use crate::terminal::{Signal, TerminalSession};
+use crate::theme::Theme;
+use crate::tree_model::{TreeModel, Update};
+use crate::user_config::UserConfig;
+use ratatui::crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    poll, read,
 };
 use ratatui::prelude::*;
-use ratatui::widgets::{Block, BorderType, Borders, List, ListItem, ListState, Paragraph};
-use std::collections::HashSet;
-use std::fs;
-use std::io::stdout;
+use ratatui::widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+/// How long to wait for a directory listing before drawing a spinner instead.
+const LOAD_WAIT: Duration = Duration::from_millis(50);
+
+/// Interval between redraws while background work is in progress.
+const SPINNER_TICK: Duration = Duration::from_millis(100);
+
+/// Rows scrolled per step of the mouse wheel.
+const SCROLL_LINES: usize = 3;
+
+/// Width of the `"> "` cursor symbol in front of every list row.
+const HIGHLIGHT_WIDTH: u16 = 2;
+
+/// Actions listed in the help bar of the tree view. The help overlay lists all.
+const HELP_BAR_ACTIONS: [Action; 16] = [
+    Action::Toggle,
+    Action::Expand,
+    Action::Collapse,
+    Action::Confirm,
+    Action::Search,
+    Action::AddImports,
+    Action::OutlineFile,
+    Action::OutlineAll,
+    Action::SelectAll,
+    Action::DeselectAll,
+    Action::Undo,
+    Action::Redo,
+    Action::ToggleHidden,
+    Action::Reload,
+    Action::Help,
+    Action::Quit,
+];
+
+/// Lines of context kept around matches when only matching regions are exported.
+const SEARCH_CONTEXT: usize = 3;
+
+/// Number of added files named in the status bar after following imports.
+const MAX_LISTED_DEPENDENCIES: usize = 3;
+
+/// Number of selection changes that can be undone.
+const UNDO_LIMIT: usize = 100;
 
 #[derive(Debug, Clone)]
 pub struct FileItem {
@@ -22,217 +74,490 @@ pub struct FileItem {
 }
 
 pub struct FileSelector {
-    base_path: PathBuf,
+    workspace: Workspace,
     items: Vec<FileItem>,
     list_state: ListState,
+    /// Where the file list was last drawn, used to map mouse clicks to rows
+    list_area: Rect,
     selected_files: HashSet<PathBuf>,
     show_hidden: bool,
     expanded_dirs: HashSet<PathBuf>,
+    status_message: Option<String>,
+    outline_mode: bool,
+    outline_files: HashSet<PathBuf>,
+    tree: TreeModel,
+    spinner_frame: usize,
+    /// Content search opened with `/`, shown instead of the tree
+    search: Option<SearchView>,
+    /// Files selected from search results with only their matching regions
+    line_ranges: BTreeMap<PathBuf, Vec<LineRange>>,
+    keymap: Keymap,
+    theme: Theme,
+    /// Whether the overlay listing all key bindings is open
+    show_help: bool,
+    /// Earlier and undone selections, for undo and redo
+    history: History<SelectionState>,
+    /// Whether the selection may have changed since it was last recorded
+    selection_changed: bool,
+}
+
+/// The selected files and their line ranges, as kept in the undo history.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct SelectionState {
+    files: HashSet<PathBuf>,
+    line_ranges: BTreeMap<PathBuf, Vec<LineRange>>,
+}
+
+/// State of the content search view.
+struct SearchView {
+    query: String,
+    literal: bool,
+    /// Whether the query is being typed
+    editing: bool,
+    results: Vec<FileMatch>,
+    list_state: ListState,
+    /// Whether files selected from the results export only their matching regions
+    regions_only: bool,
+    /// Results of a search still running in the background
+    pending: Option<Receiver<Vec<FileMatch>>>,
 }
 
 impl FileSelector {
     pub fn new(base_path: &Path) -> Self {
+        Self::with_workspace(Workspace::single(base_path))
+    }
+
+    /// Creates a selector over a workspace. Each root of a multi-root
+    /// workspace is shown as its own top-level node.
+    pub fn with_workspace(workspace: Workspace) -> Self {
         let mut selector = Self {
-            base_path: base_path.to_path_buf(),
+            workspace,
             items: Vec::new(),
             list_state: ListState::default(),
+            list_area: Rect::default(),
             selected_files: HashSet::new(),
             show_hidden: false,
             expanded_dirs: HashSet::new(),
+            status_message: None,
+            outline_mode: false,
+            outline_files: HashSet::new(),
+            tree: TreeModel::new(),
+            spinner_frame: 0,
+            search: None,
+            line_ranges: BTreeMap::new(),
+            keymap: Keymap::default(),
+            theme: Theme::default(),
+            show_help: false,
+            history: History::new(SelectionState::default(), UNDO_LIMIT),
+            selection_changed: false,
         };
 
-        // Initially expand the base directory
-        selector.expanded_dirs.insert(base_path.to_path_buf());
+        // Initially expand the root directories
+        let roots: Vec<PathBuf> = selector
+            .workspace
+            .roots()
+            .iter()
+            .map(|root| root.path.clone())
+            .collect();
+        selector.expanded_dirs.extend(roots);
         selector.refresh_items().unwrap_or(());
+        selector.wait_for_listings();
         selector.list_state.select(Some(0));
         selector
     }
 
     pub fn set_selections(&mut self, selections: Vec<PathBuf>) {
         self.selected_files = selections.into_iter().collect();
-        self.refresh_items().unwrap_or(());
+        self.sync_selection_marks(None);
+    }
+
+    /// Replaces the default key bindings, e.g. with ones from the user configuration.
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
     }
 
+    /// Replaces the default colors and symbols, e.g. with ones from the user configuration.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    /// Returns `true` if the user switched on outline mode for all files.
+    pub fn outline_mode(&self) -> bool {
+        self.outline_mode
+    }
+
+    /// Returns the files the user marked for outline export.
+    pub fn outline_files(&self) -> Vec<PathBuf> {
+        self.outline_files
+            .iter()
+            .filter(|path| self.selected_files.contains(*path))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the line ranges of selected files that export only the regions
+    /// matching a search.
+    pub fn line_ranges(&self) -> BTreeMap<PathBuf, Vec<LineRange>> {
+        self.line_ranges
+            .iter()
+            .filter(|(path, _)| self.selected_files.contains(*path))
+            .map(|(path, ranges)| (path.clone(), ranges.clone()))
+            .collect()
+    }
+
+    /// Rebuilds the visible rows from the cached tree, requesting listings
+    /// of expanded directories that are not loaded yet. No directory is read
+    /// on the calling thread.
     fn refresh_items(&mut self) -> Result<()> {
-        self.items.clear();
-        let base_path = self.base_path.clone();
+        let roots = self.workspace.roots().to_vec();
 
-        // Validate base path still exists
-        if !base_path.exists() {
-            return Err(anyhow::anyhow!(
+        // Validate base paths still exist
+        if let Some(missing) = roots.iter().find(|root| !root.path.exists()) {
+            return Err(TreeTxtError::InvalidPath(format!(
                 "Base directory no longer exists: {}",
-                base_path.display()
-            ));
+                missing.path.display()
+            )));
         }
 
-        self.build_tree(&base_path, 0, None)
-            .map_err(|e| anyhow::anyhow!("Failed to read directory structure: {}", e))?;
-        self.update_item_selections();
+        let cursor_path = self.current_item().map(|item| item.path.clone());
+        let mut items = Vec::new();
+        if self.workspace.is_multi_root() {
+            // One top-level node per root, labelled like the export
+            for root in roots {
+                let is_expanded = self.expanded_dirs.contains(&root.path);
+                items.push(FileItem {
+                    path: root.path.clone(),
+                    name: root.label,
+                    is_dir: true,
+                    is_selected: false,
+                    is_expanded,
+                    depth: 0,
+                });
+                if is_expanded {
+                    items.extend(self.build_tree(&root.path, 1));
+                }
+            }
+        } else {
+            items = self.build_tree(&roots[0].path, 0);
+        }
+        self.items = items;
+
+        // Keep the cursor on the same entry if it is still visible
+        if let Some(path) = cursor_path
+            && let Some(index) = self.items.iter().position(|item| item.path == path)
+        {
+            self.list_state.select(Some(index));
+        } else if let Some(selected) = self.list_state.selected() {
+            self.list_state
+                .select(Some(selected.min(self.items.len().saturating_sub(1))));
+        }
         Ok(())
     }
 
-    fn build_tree(
-        &mut self,
-        dir_path: &Path,
-        depth: usize,
-        _parent_path: Option<PathBuf>,
-    ) -> Result<()> {
-        // Read directory contents with better error handling
-        let entries = fs::read_dir(dir_path).map_err(|e| {
-            anyhow::anyhow!("Cannot read directory '{}': {}", dir_path.display(), e)
-        })?;
-        let mut items: Vec<_> = entries
-            .filter_map(|entry| entry.ok())
-            .filter(|entry| {
-                if !self.show_hidden {
-                    !entry.file_name().to_string_lossy().starts_with('.')
-                } else {
-                    true
-                }
-            })
+    /// Returns the rows below `dir_path` from the cache, recursing into
+    /// expanded directories and requesting listings that are missing.
+    fn build_tree(&mut self, dir_path: &Path, depth: usize) -> Vec<FileItem> {
+        let Some(entries) = self.tree.children(dir_path) else {
+            self.tree.request(dir_path);
+            return Vec::new();
+        };
+
+        let entries: Vec<_> = entries
+            .iter()
+            .filter(|entry| self.show_hidden || !entry.is_hidden)
+            .cloned()
             .collect();
 
-        // Sort: directories first, then files, both alphabetically
-        items.sort_by(|a, b| {
-            let a_is_dir = a.path().is_dir();
-            let b_is_dir = b.path().is_dir();
-            match (a_is_dir, b_is_dir) {
-                (true, false) => std::cmp::Ordering::Less,
-                (false, true) => std::cmp::Ordering::Greater,
-                _ => a.file_name().cmp(&b.file_name()),
-            }
-        });
+        let mut items = Vec::new();
+        for entry in entries {
+            let is_expanded = entry.is_dir && self.expanded_dirs.contains(&entry.path);
+            let is_selected = !entry.is_dir && self.selected_files.contains(&entry.path);
 
-        for entry in items {
-            let path = entry.path();
-            let name = entry.file_name().to_string_lossy().to_string();
-            let is_dir = path.is_dir();
-            let is_expanded = is_dir && self.expanded_dirs.contains(&path);
-            let is_selected = !is_dir && self.selected_files.contains(&path);
-
-            self.items.push(FileItem {
-                path: path.clone(),
-                name,
-                is_dir,
+            items.push(FileItem {
+                path: entry.path.clone(),
+                name: entry.name,
+                is_dir: entry.is_dir,
                 is_selected,
                 is_expanded,
                 depth,
             });
 
             // Recursively build tree for expanded directories
-            if is_dir && is_expanded {
-                self.build_tree(&path, depth + 1, Some(dir_path.to_path_buf()))?;
+            if is_expanded {
+                items.extend(self.build_tree(&entry.path, depth + 1));
             }
         }
 
-        Ok(())
+        items
     }
 
-    fn update_item_selections(&mut self) {
+    /// Inserts the rows of a newly listed or expanded directory below it.
+    fn insert_children(&mut self, dir_path: &Path) {
+        let (at, depth) = if !self.workspace.is_multi_root() && dir_path == self.workspace.primary()
+        {
+            if !self.items.is_empty() {
+                return;
+            }
+            (0, 0)
+        } else {
+            let Some(index) = self.items.iter().position(|item| item.path == dir_path) else {
+                return;
+            };
+            let item = &self.items[index];
+            let has_children = self
+                .items
+                .get(index + 1)
+                .is_some_and(|next| next.depth > item.depth);
+            if !item.is_expanded || has_children {
+                return;
+            }
+            (index + 1, item.depth + 1)
+        };
+
+        let rows = self.build_tree(dir_path, depth);
+        let cursor = self.list_state.selected();
+        let inserted = rows.len();
+        self.items.splice(at..at, rows);
+        if let Some(cursor) = cursor
+            && cursor >= at
+            && at > 0
+        {
+            self.list_state.select(Some(cursor + inserted));
+        }
+    }
+
+    /// Updates the selection marks of the rows below `prefix` (or of all rows).
+    fn sync_selection_marks(&mut self, prefix: Option<&Path>) {
         for item in &mut self.items {
-            if !item.is_dir {
+            if !item.is_dir && prefix.is_none_or(|prefix| item.path.starts_with(prefix)) {
                 item.is_selected = self.selected_files.contains(&item.path);
             }
         }
     }
 
-    pub fn run_interactive(&mut self) -> Result<Vec<PathBuf>> {
-        enable_raw_mode()?;
-        stdout().execute(EnterAlternateScreen)?;
+    /// Applies finished background loads and walks.
+    fn apply_updates(&mut self, updates: Vec<Update>) {
+        for update in updates {
+            match update {
+                Update::Listed(dir) => {
+                    if self.expanded_dirs.contains(&dir) {
+                        self.insert_children(&dir);
+                    }
+                }
+                Update::Failed { dir, message } => {
+                    if self.expanded_dirs.contains(&dir) {
+                        self.status_message = Some(format!(
+                            "Cannot read directory '{}': {message}",
+                            self.workspace.display_path(&dir).display()
+                        ));
+                    }
+                }
+                Update::Walked { dir, files } => self.toggle_directory_files(&dir, files),
+            }
+        }
+    }
 
-        let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
-        let result = self.run_event_loop(&mut terminal);
+    /// Waits briefly for pending listings, so small directories appear
+    /// without a spinner flashing up.
+    fn wait_for_listings(&mut self) {
+        let updates = self.tree.wait(LOAD_WAIT);
+        self.apply_updates(updates);
+    }
 
-        // Cleanup
-        disable_raw_mode()?;
-        stdout().execute(LeaveAlternateScreen)?;
+    fn current_item(&self) -> Option<&FileItem> {
+        self.list_state
+            .selected()
+            .and_then(|selected| self.items.get(selected))
+    }
 
-        result
+    pub fn run_interactive(&mut self) -> Result<Vec<PathBuf>> {
+        // The session restores the terminal when dropped, also on errors
+        let mut session = TerminalSession::start()?;
+        let selection = self.run_event_loop(&mut session)?;
+        session.finish()?;
+        Ok(selection)
     }
 
-    fn run_event_loop(
-        &mut self,
-        terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
-    ) -> Result<Vec<PathBuf>> {
+    fn run_event_loop(&mut self, session: &mut TerminalSession) -> Result<Vec<PathBuf>> {
+        // Selections restored from a previous session are the starting point
+        self.history = History::new(self.selection_state(), UNDO_LIMIT);
+
         loop {
+            let updates = self.tree.poll();
+            self.apply_updates(updates);
+            self.poll_search();
+            // The changes made since the last pass become one undo step,
+            // including directory selections finished in the background
+            if std::mem::take(&mut self.selection_changed) {
+                self.history.record(&self.selection_state());
+            }
+
             // Render the interface
-            terminal.draw(|f| {
-                self.render_ui(f);
-            })?;
+            session
+                .terminal()
+                .draw(|f| {
+                    self.render_ui(f);
+                })
+                .map_err(TreeTxtError::terminal("draw the interface"))?;
+
+            // Wake up regularly to react to signals and keep the spinner
+            // moving while directories load or a search runs in the background
+            let ready = poll(SPINNER_TICK).map_err(TreeTxtError::terminal("read input"))?;
+            match session.pending_signal() {
+                Some(Signal::Terminate) => return Err(TreeTxtError::Cancelled),
+                Some(Signal::Suspend) => {
+                    session.suspend()?;
+                    continue;
+                }
+                None => {}
+            }
+            if !ready {
+                if self.tree.is_busy() || self.is_searching() {
+                    self.spinner_frame =
+                        (self.spinner_frame + 1) % self.theme.symbols.spinner.len();
+                }
+                continue;
+            }
 
             // Handle events
-            let event = read()?;
+            let event = read().map_err(TreeTxtError::terminal("read input"))?;
+            if let Event::Mouse(mouse) = event {
+                if !self.show_help {
+                    self.handle_mouse(mouse);
+                } else if matches!(mouse.kind, MouseEventKind::Down(_)) {
+                    // A click closes the help overlay without reaching the rows below it
+                    self.show_help = false;
+                }
+                continue;
+            }
             if let Event::Key(key) = event
                 && key.kind == KeyEventKind::Press
             {
-                match key.code {
-                    KeyCode::Char('q') => break,
-                    KeyCode::Enter => {
-                        // ENTER always confirms selections
-                        return Ok(self.selected_files.iter().cloned().collect());
-                    }
-                    KeyCode::Char(' ') => {
-                        self.toggle_selection();
+                // Raw mode delivers Ctrl+C and Ctrl+Z as keys instead of signals
+                match KeyBinding::from_event(&key) {
+                    KeyBinding::CANCEL => return Err(TreeTxtError::Cancelled),
+                    KeyBinding::SUSPEND => {
+                        session.suspend()?;
+                        continue;
                     }
-                    KeyCode::Right | KeyCode::Char('l') => {
-                        self.expand_current_directory();
-                    }
-                    KeyCode::Left => {
-                        self.collapse_current_directory();
-                    }
-                    KeyCode::Char('a') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        self.select_all_files();
+                    _ => {}
+                }
+                self.status_message = None;
+                if self.show_help {
+                    // Any key closes the help overlay
+                    self.show_help = false;
+                    continue;
+                }
+                if self.search.is_some() && self.handle_search_key(&key) {
+                    continue;
+                }
+                let Some(action) = self.keymap.action_for(&key) else {
+                    continue;
+                };
+                match action {
+                    Action::Quit => break,
+                    Action::Confirm => {
+                        // Confirming always includes directories still being scanned
+                        while self.tree.is_busy() {
+                            let updates = self.tree.wait(SPINNER_TICK);
+                            self.apply_updates(updates);
+                        }
+                        return Ok(self.sorted_selection());
                     }
-                    KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        self.deselect_all();
+                    Action::Toggle => self.toggle_selection(),
+                    Action::Expand => self.expand_current_directory(),
+                    Action::Collapse => self.collapse_current_directory(),
+                    Action::Down => self.move_cursor(1),
+                    Action::Up => self.move_cursor(-1),
+                    Action::First => self.move_cursor(isize::MIN),
+                    Action::Last => self.move_cursor(isize::MAX),
+                    Action::PageDown => self.move_cursor(self.page_size()),
+                    Action::PageUp => self.move_cursor(-self.page_size()),
+                    Action::OutlineFile => self.toggle_outline(),
+                    Action::OutlineAll => {
+                        self.outline_mode = !self.outline_mode;
+                        self.status_message = Some(if self.outline_mode {
+                            "Outline mode on: all source files export signatures only".to_string()
+                        } else {
+                            "Outline mode off".to_string()
+                        });
                     }
-                    KeyCode::Char('h') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    Action::Search => self.open_search(),
+                    Action::AddImports => self.select_dependencies(),
+                    Action::SelectAll => self.select_all_files(),
+                    Action::DeselectAll => self.deselect_all(),
+                    Action::Undo => self.undo(),
+                    Action::Redo => self.redo(),
+                    Action::ToggleHidden => {
                         self.show_hidden = !self.show_hidden;
                         self.refresh_items()?;
                     }
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        self.move_selection_down();
-                    }
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        self.move_selection_up();
+                    Action::Reload => {
+                        self.tree.clear();
+                        self.refresh_items()?;
+                        self.wait_for_listings();
+                        self.status_message = Some("Reloaded the directory tree".to_string());
                     }
-                    _ => {}
+                    Action::Help => self.show_help = true,
+                    // Only meaningful in the search results
+                    Action::SelectMatches | Action::MatchingLines | Action::Back => {}
                 }
             }
         }
 
-        Ok(self.selected_files.iter().cloned().collect())
+        Ok(self.sorted_selection())
     }
 
-    fn render_ui(&self, f: &mut Frame) {
+    /// Returns the selected files in path order, so exports are reproducible.
+    fn sorted_selection(&self) -> Vec<PathBuf> {
+        let mut selection: Vec<PathBuf> = self.selected_files.iter().cloned().collect();
+        selection.sort();
+        selection
+    }
+
+    fn render_ui(&mut self, f: &mut Frame) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Min(0), Constraint::Length(4)])
+            .constraints([Constraint::Min(0), Constraint::Length(5)])
             .split(f.area());
 
         // Create list items with visual indicators and tree structure
+        let symbols = self.theme.symbols;
         let items: Vec<ListItem> = self
             .items
             .iter()
             .map(|item| {
-                let mut style = Style::default();
-
                 // Create indentation based on depth
                 let indent = "  ".repeat(item.depth);
 
-                let (prefix, suffix) = if item.is_dir {
-                    style = style.fg(Color::Cyan).add_modifier(Modifier::BOLD);
-                    let expand_indicator = if item.is_expanded { "▼ " } else { "▶ " };
+                let (prefix, suffix, style) = if item.is_dir {
+                    let spinner = format!("{} ", symbols.spinner[self.spinner_frame]);
+                    let expand_indicator = if item.is_expanded && self.tree.is_loading(&item.path)
+                        || self.tree.is_walking(&item.path)
+                    {
+                        spinner.as_str()
+                    } else if item.is_expanded {
+                        symbols.expanded
+                    } else {
+                        symbols.collapsed
+                    };
                     (
-                        format!("{}📁 {}", expand_indicator, item.name),
+                        format!("{}{}{}", expand_indicator, symbols.folder, item.name),
                         "/".to_string(),
+                        self.theme.directory,
                     )
                 } else if item.is_selected {
-                    style = style.fg(Color::Green).add_modifier(Modifier::BOLD);
-                    ("✓ ".to_string(), item.name.clone())
+                    (
+                        symbols.checked.to_string(),
+                        self.file_label(item),
+                        self.theme.selected,
+                    )
                 } else {
-                    style = style.fg(Color::White);
-                    ("  ".to_string(), item.name.clone())
+                    (
+                        symbols.unchecked.to_string(),
+                        self.file_label(item),
+                        self.theme.unselected,
+                    )
                 };
 
                 let display_text = format!("{indent}{prefix}{suffix}");
@@ -247,39 +572,463 @@ impl FileSelector {
                 Block::default()
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
-                    .title(format!("Files in: {}", self.base_path.display())),
-            )
-            .highlight_style(
-                Style::default()
-                    .bg(Color::DarkGray)
-                    .add_modifier(Modifier::BOLD),
+                    .border_style(self.theme.border)
+                    .title(self.title()),
             )
+            .highlight_style(self.theme.cursor)
             .highlight_symbol("> ");
 
-        f.render_stateful_widget(list, chunks[0], &mut self.list_state.clone());
+        self.list_area = chunks[0];
+        if self.search.is_some() {
+            self.render_search_results(f, chunks[0]);
+        } else {
+            f.render_stateful_widget(list, chunks[0], &mut self.list_state);
+        }
 
         // Render help and status
         let selected_count = self.selected_files.len();
-        let help_text = format!(
-            "Selected: {selected_count} files | SPACE=select/select dir | →=expand | ←=collapse | ENTER=confirm | ↑↓=navigate | Ctrl+A=select all | Ctrl+D=clear | Ctrl+H=toggle hidden | Q=quit"
-        );
+        let mode = if self.outline_mode { "outline" } else { "full" };
+        let help_text = match &self.search {
+            Some(search) if search.editing => {
+                "Type a query | Tab=regex/literal | Enter=search | Esc=cancel".to_string()
+            }
+            Some(search) => {
+                let regions = if search.regions_only { "on" } else { "off" };
+                let hints = [
+                    self.key_hint(Action::Toggle, "select"),
+                    self.key_hint(Action::SelectMatches, Action::SelectMatches.label()),
+                    self.key_hint(
+                        Action::MatchingLines,
+                        &format!("{} ({regions})", Action::MatchingLines.label()),
+                    ),
+                    self.key_hint(Action::Search, "edit query"),
+                    self.key_hint(Action::Back, Action::Back.label()),
+                    self.key_hint(Action::Confirm, "confirm"),
+                    self.key_hint(Action::Help, "help"),
+                    self.key_hint(Action::Quit, "quit"),
+                ];
+                let hints: Vec<String> = hints.into_iter().flatten().collect();
+                format!("Selected: {selected_count} files | {}", hints.join(" | "))
+            }
+            None => {
+                let hints: Vec<String> = HELP_BAR_ACTIONS
+                    .iter()
+                    .filter_map(|&action| self.key_hint(action, action.label()))
+                    .collect();
+                format!(
+                    "Selected: {selected_count} files | Mode: {mode} | {}",
+                    hints.join(" | ")
+                )
+            }
+        };
+
+        let mut lines = Vec::new();
+        if let Some(search) = &self.search
+            && search.editing
+        {
+            let kind = if search.literal { "text" } else { "regex" };
+            lines.push(Line::styled(
+                format!("Search ({kind}): {}{}", search.query, symbols.prompt_cursor),
+                self.theme.unselected.add_modifier(Modifier::BOLD),
+            ));
+        }
+        if let Some(message) = &self.status_message {
+            lines.push(Line::styled(message.clone(), self.theme.status));
+        }
+        if self.tree.is_busy() {
+            lines.push(Line::raw(format!(
+                "{} Loading directories...",
+                symbols.spinner[self.spinner_frame]
+            )));
+        }
+        if let Some(search) = &self.search
+            && search.pending.is_some()
+        {
+            lines.push(Line::raw(format!(
+                "{} Searching for '{}'...",
+                symbols.spinner[self.spinner_frame], search.query
+            )));
+        }
+        lines.push(Line::raw(help_text));
 
-        let status_paragraph = Paragraph::new(help_text)
-            .block(Block::default().borders(Borders::ALL).title("Controls"))
-            .style(Style::default().fg(Color::Yellow))
+        let status_paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(self.theme.border)
+                    .title("Controls"),
+            )
+            .style(self.theme.help)
             .wrap(ratatui::widgets::Wrap { trim: true });
 
         f.render_widget(status_paragraph, chunks[1]);
+
+        if self.show_help {
+            self.render_help(f);
+        }
+    }
+
+    /// Returns `key=label` for the first key bound to `action`, or `None` if
+    /// the action has no key.
+    fn key_hint(&self, action: Action, label: &str) -> Option<String> {
+        self.keymap
+            .primary_key(action)
+            .map(|key| format!("{key}={label}"))
+    }
+
+    /// Draws the overlay listing every action and its keys.
+    fn render_help(&self, f: &mut Frame) {
+        let keys: Vec<(String, &str)> = Action::ALL
+            .iter()
+            .map(|&action| (self.keymap.keys_label(action), action.description()))
+            .collect();
+        let key_width = keys
+            .iter()
+            .map(|(keys, _)| keys.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut lines: Vec<Line> = keys
+            .iter()
+            .map(|(keys, description)| {
+                let keys = if keys.is_empty() { "(unbound)" } else { keys };
+                Line::from(vec![
+                    Span::styled(
+                        format!("{keys:<key_width$}  "),
+                        self.theme.help.add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw(*description),
+                ])
+            })
+            .collect();
+        lines.push(Line::raw(""));
+        lines.push(Line::raw(format!(
+            "Customize in {}",
+            UserConfig::path().display()
+        )));
+
+        let area = f.area();
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let width = area.width.saturating_sub(4).min(90);
+        let overlay = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+
+        let help = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(self.theme.border)
+                .title("Key bindings (press any key to close)"),
+        );
+        f.render_widget(Clear, overlay);
+        f.render_widget(help, overlay);
+    }
+
+    fn render_search_results(&mut self, f: &mut Frame, area: Rect) {
+        let Some(search) = &self.search else {
+            return;
+        };
+        let symbols = self.theme.symbols;
+        let items: Vec<ListItem> = search
+            .results
+            .iter()
+            .map(|found| {
+                let selected = self.selected_files.contains(&found.path);
+                let (prefix, style) = if selected {
+                    (symbols.checked, self.theme.selected)
+                } else {
+                    (symbols.unchecked, self.theme.unselected)
+                };
+                let regions = if selected && self.line_ranges.contains_key(&found.path) {
+                    symbols.regions
+                } else {
+                    ""
+                };
+                ListItem::new(format!(
+                    "{prefix}{} ({} hit{}){regions}",
+                    self.workspace.display_path(&found.path).display(),
+                    found.hits,
+                    if found.hits == 1 { "" } else { "s" }
+                ))
+                .style(style)
+            })
+            .collect();
+
+        let title = if search.query.is_empty() {
+            "Search file contents".to_string()
+        } else {
+            format!("Files matching: {}", search.query)
+        };
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(self.theme.border)
+                    .title(title),
+            )
+            .highlight_style(self.theme.cursor)
+            .highlight_symbol("> ");
+
+        if let Some(search) = &mut self.search {
+            f.render_stateful_widget(list, area, &mut search.list_state);
+        }
+    }
+
+    fn title(&self) -> String {
+        if self.workspace.is_multi_root() {
+            let labels: Vec<&str> = self
+                .workspace
+                .roots()
+                .iter()
+                .map(|root| root.label.as_str())
+                .collect();
+            format!("Workspace: {}", labels.join(", "))
+        } else {
+            format!("Files in: {}", self.workspace.primary().display())
+        }
+    }
+
+    fn file_label(&self, item: &FileItem) -> String {
+        if self.outline_files.contains(&item.path) {
+            format!("{}{}", item.name, self.theme.symbols.outline)
+        } else if self.line_ranges.contains_key(&item.path) {
+            format!("{}{}", item.name, self.theme.symbols.regions)
+        } else {
+            item.name.clone()
+        }
+    }
+
+    fn open_search(&mut self) {
+        match &mut self.search {
+            Some(search) => search.editing = true,
+            None => {
+                self.search = Some(SearchView {
+                    query: String::new(),
+                    literal: false,
+                    editing: true,
+                    results: Vec::new(),
+                    list_state: ListState::default(),
+                    regions_only: false,
+                    pending: None,
+                });
+            }
+        }
+    }
+
+    /// Handles a key while the search view is open. Returns `false` for keys
+    /// that keep their meaning from the tree view (quit, confirm and help).
+    fn handle_search_key(&mut self, key: &KeyEvent) -> bool {
+        let Some(search) = &mut self.search else {
+            return false;
+        };
+
+        if search.editing {
+            match key.code {
+                KeyCode::Enter => self.run_search(),
+                KeyCode::Esc if search.results.is_empty() => self.search = None,
+                KeyCode::Esc => search.editing = false,
+                KeyCode::Tab => search.literal = !search.literal,
+                KeyCode::Backspace => {
+                    search.query.pop();
+                }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    search.query.push(c);
+                }
+                _ => {}
+            }
+            return true;
+        }
+
+        match self.keymap.action_for(key) {
+            Some(Action::Quit | Action::Confirm | Action::Help | Action::Undo | Action::Redo) => {
+                return false;
+            }
+            Some(Action::Down) => self.move_cursor(1),
+            Some(Action::Up) => self.move_cursor(-1),
+            Some(Action::First) => self.move_cursor(isize::MIN),
+            Some(Action::Last) => self.move_cursor(isize::MAX),
+            Some(Action::PageDown) => self.move_cursor(self.page_size()),
+            Some(Action::PageUp) => self.move_cursor(-self.page_size()),
+            Some(Action::Search) => search.editing = true,
+            Some(Action::Toggle) => {
+                if let Some(found) = search
+                    .list_state
+                    .selected()
+                    .and_then(|selected| search.results.get(selected))
+                    .cloned()
+                {
+                    self.toggle_search_results(&[found]);
+                }
+            }
+            Some(Action::SelectAll | Action::SelectMatches) => {
+                let results = search.results.clone();
+                self.toggle_search_results(&results);
+            }
+            Some(Action::MatchingLines) => self.toggle_regions_only(),
+            Some(Action::Back) => self.search = None,
+            Some(_) | None => {}
+        }
+        true
+    }
+
+    /// Switches between exporting whole files and only the matching regions
+    /// of files selected from the search results.
+    fn toggle_regions_only(&mut self) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        search.regions_only = !search.regions_only;
+        let regions_only = search.regions_only;
+        self.selection_changed = true;
+
+        for found in &search.results {
+            if regions_only && self.selected_files.contains(&found.path) {
+                self.line_ranges
+                    .insert(found.path.clone(), found.regions(SEARCH_CONTEXT));
+            } else if !regions_only {
+                self.line_ranges.remove(&found.path);
+            }
+        }
+        self.status_message = Some(if regions_only {
+            format!("Exporting only matching lines with {SEARCH_CONTEXT} lines of context")
+        } else {
+            "Exporting whole files".to_string()
+        });
+    }
+
+    /// Starts searching the workspace for the query being edited on a
+    /// background thread. [`poll_search`](Self::poll_search) shows the results.
+    fn run_search(&mut self) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        if search.query.is_empty() {
+            return;
+        }
+
+        match Query::new(&search.query, search.literal, false) {
+            Ok(query) => {
+                let (sender, receiver) = mpsc::channel();
+                let workspace = self.workspace.clone();
+                let include_hidden = self.show_hidden;
+                thread::spawn(move || {
+                    let _ = sender.send(search::search(&workspace, &query, include_hidden));
+                });
+
+                // A search started earlier is abandoned with its receiver
+                search.pending = Some(receiver);
+                search.results.clear();
+                search.list_state.select(None);
+                search.editing = false;
+                self.status_message = None;
+            }
+            Err(err) => self.status_message = Some(err.to_string()),
+        }
+    }
+
+    /// Shows the results of the background search once it has finished.
+    fn poll_search(&mut self) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        let Some(receiver) = &search.pending else {
+            return;
+        };
+
+        match receiver.try_recv() {
+            Ok(results) => {
+                search.pending = None;
+                search.results = results;
+                search.list_state.select(Some(0));
+                let hits: usize = search.results.iter().map(|found| found.hits).sum();
+                self.status_message = Some(format!(
+                    "{} file{} with {hits} match{}",
+                    search.results.len(),
+                    if search.results.len() == 1 { "" } else { "s" },
+                    if hits == 1 { "" } else { "es" }
+                ));
+            }
+            Err(TryRecvError::Empty) => {}
+            Err(TryRecvError::Disconnected) => {
+                search.pending = None;
+                self.status_message = Some("The search stopped unexpectedly".to_string());
+            }
+        }
+    }
+
+    /// Returns `true` while a search is running in the background.
+    fn is_searching(&self) -> bool {
+        self.search
+            .as_ref()
+            .is_some_and(|search| search.pending.is_some())
+    }
+
+    /// Selects the files in `results`, or deselects them if they are all
+    /// selected already.
+    fn toggle_search_results(&mut self, results: &[FileMatch]) {
+        let regions_only = self
+            .search
+            .as_ref()
+            .is_some_and(|search| search.regions_only);
+        let all_selected = results
+            .iter()
+            .all(|found| self.selected_files.contains(&found.path));
+
+        if all_selected {
+            for found in results {
+                self.selected_files.remove(&found.path);
+                self.line_ranges.remove(&found.path);
+            }
+        } else {
+            let paths: Vec<PathBuf> = results.iter().map(|found| found.path.clone()).collect();
+            self.warn_if_sensitive(&paths);
+            for found in results {
+                self.selected_files.insert(found.path.clone());
+                if regions_only {
+                    self.line_ranges
+                        .insert(found.path.clone(), found.regions(SEARCH_CONTEXT));
+                }
+            }
+        }
+
+        self.selection_changed = true;
+        self.sync_selection_marks(None);
+    }
+
+    fn toggle_outline(&mut self) {
+        if let Some(selected) = self.list_state.selected()
+            && selected < self.items.len()
+            && !self.items[selected].is_dir
+        {
+            let path = self.items[selected].path.clone();
+            if !self.outline_files.remove(&path) {
+                self.outline_files.insert(path.clone());
+                // Outlining a file implies exporting it
+                self.selected_files.insert(path);
+                self.selection_changed = true;
+                self.items[selected].is_selected = true;
+            }
+        }
     }
 
     fn expand_current_directory(&mut self) {
         if let Some(selected) = self.list_state.selected()
             && selected < self.items.len()
         {
-            let item = &self.items[selected];
+            let item = &mut self.items[selected];
             if item.is_dir && !item.is_expanded {
-                self.expanded_dirs.insert(item.path.clone());
-                self.refresh_items().unwrap_or(());
+                item.is_expanded = true;
+                let path = item.path.clone();
+                self.expanded_dirs.insert(path.clone());
+                self.insert_children(&path);
+                if self.tree.is_loading(&path) {
+                    self.wait_for_listings();
+                }
             }
         }
     }
@@ -288,10 +1037,18 @@ impl FileSelector {
         if let Some(selected) = self.list_state.selected()
             && selected < self.items.len()
         {
-            let item = &self.items[selected];
+            let item = &mut self.items[selected];
             if item.is_dir && item.is_expanded {
+                item.is_expanded = false;
+                let depth = item.depth;
                 self.expanded_dirs.remove(&item.path);
-                self.refresh_items().unwrap_or(());
+
+                // Remove the directory's descendants, which follow it with a greater depth
+                let end = self.items[selected + 1..]
+                    .iter()
+                    .position(|item| item.depth <= depth)
+                    .map_or(self.items.len(), |offset| selected + 1 + offset);
+                self.items.drain(selected + 1..end);
             }
         }
     }
@@ -304,24 +1061,26 @@ impl FileSelector {
             let is_dir = self.items[selected].is_dir;
 
             if is_dir {
-                // Select all visible files in this directory
-                self.select_directory_files(&item_path);
+                // Select all files in this directory, found by a background walk
+                self.tree.walk(&item_path, self.show_hidden);
+                self.wait_for_listings();
             } else {
                 // Toggle individual file selection
                 if self.selected_files.contains(&item_path) {
                     self.selected_files.remove(&item_path);
+                    self.line_ranges.remove(&item_path);
                 } else {
-                    self.selected_files.insert(item_path);
+                    self.warn_if_sensitive(std::slice::from_ref(&item_path));
+                    self.selected_files.insert(item_path.clone());
                 }
-                self.refresh_items().unwrap_or(());
+                self.items[selected].is_selected = self.selected_files.contains(&item_path);
+                self.selection_changed = true;
             }
         }
     }
 
-    fn select_directory_files(&mut self, dir_path: &Path) {
-        // Get all files in directory recursively (not just visible ones)
-        let files_in_dir: Vec<PathBuf> = self.get_all_files_in_directory(dir_path);
-
+    /// Selects `files_in_dir`, or deselects them if they are all selected already.
+    fn toggle_directory_files(&mut self, dir_path: &Path, files_in_dir: Vec<PathBuf>) {
         // Check if all files in this directory are already selected
         let all_selected = files_in_dir.iter().all(|f| self.selected_files.contains(f));
 
@@ -329,77 +1088,270 @@ impl FileSelector {
             // Deselect all files in this directory
             for file_path in files_in_dir {
                 self.selected_files.remove(&file_path);
+                self.line_ranges.remove(&file_path);
             }
         } else {
             // Select all files in this directory
-            for file_path in files_in_dir {
-                self.selected_files.insert(file_path);
-            }
+            self.warn_if_sensitive(&files_in_dir);
+            self.selected_files.extend(files_in_dir);
         }
 
-        self.refresh_items().unwrap_or(());
+        self.selection_changed = true;
+        self.sync_selection_marks(Some(dir_path));
     }
 
-    fn get_all_files_in_directory(&self, dir_path: &Path) -> Vec<PathBuf> {
-        let mut files = Vec::new();
+    fn warn_if_sensitive(&mut self, files: &[PathBuf]) {
+        if let Some(warning) = self.sensitive_warning(files) {
+            self.status_message = Some(warning);
+        }
+    }
 
-        let entries = match fs::read_dir(dir_path) {
-            Ok(entries) => entries,
-            Err(e) => {
-                eprintln!(
-                    "Warning: Cannot read directory '{}': {}",
-                    dir_path.display(),
-                    e
-                );
-                return files;
-            }
+    /// Returns a warning naming the files in `files` that may contain secrets.
+    fn sensitive_warning(&self, files: &[PathBuf]) -> Option<String> {
+        let sensitive: Vec<String> = files
+            .iter()
+            .filter(|path| is_sensitive_filename(path))
+            .map(|path| self.workspace.display_path(path).display().to_string())
+            .collect();
+
+        (!sensitive.is_empty()).then(|| {
+            format!(
+                "{}Selected file(s) may contain secrets: {}",
+                self.theme.symbols.warning,
+                sensitive.join(", ")
+            )
+        })
+    }
+
+    fn select_all_files(&mut self) {
+        // Select all visible files in the entire tree
+        let files: Vec<PathBuf> = self
+            .items
+            .iter()
+            .filter(|item| !item.is_dir && !self.selected_files.contains(&item.path))
+            .map(|item| item.path.clone())
+            .collect();
+        self.warn_if_sensitive(&files);
+        self.selected_files.extend(files);
+        self.selection_changed = true;
+        self.sync_selection_marks(None);
+    }
+
+    /// Adds the local files imported by the selected files, one level deep per
+    /// key press, and lists what was added in the status bar, after a warning
+    /// if any of them may contain secrets.
+    fn select_dependencies(&mut self) {
+        let added = dependencies::follow(&self.workspace, &self.sorted_selection(), 1);
+        if added.is_empty() {
+            self.status_message = Some("No imported files to add".to_string());
+            return;
+        }
+
+        let mut reasons: Vec<String> = added
+            .iter()
+            .take(MAX_LISTED_DEPENDENCIES)
+            .map(|file| {
+                format!(
+                    "{} (imported by {})",
+                    self.workspace.display_path(&file.path).display(),
+                    self.workspace.display_path(&file.imported_by).display()
+                )
+            })
+            .collect();
+        if added.len() > MAX_LISTED_DEPENDENCIES {
+            reasons.push(format!(
+                "and {} more",
+                added.len() - MAX_LISTED_DEPENDENCIES
+            ));
+        }
+        let mut message = format!(
+            "Added {} imported file{}: {}",
+            added.len(),
+            if added.len() == 1 { "" } else { "s" },
+            reasons.join(", ")
+        );
+        let paths: Vec<PathBuf> = added.into_iter().map(|file| file.path).collect();
+        if let Some(warning) = self.sensitive_warning(&paths) {
+            message = format!("{warning}. {message}");
+        }
+        self.status_message = Some(message);
+
+        self.selected_files.extend(paths);
+        self.selection_changed = true;
+        self.sync_selection_marks(None);
+    }
+
+    fn deselect_all(&mut self) {
+        self.selected_files.clear();
+        self.line_ranges.clear();
+        self.selection_changed = true;
+        self.sync_selection_marks(None);
+    }
+
+    fn selection_state(&self) -> SelectionState {
+        SelectionState {
+            files: self.selected_files.clone(),
+            line_ranges: self.line_ranges.clone(),
+        }
+    }
+
+    fn undo(&mut self) {
+        let current = self.selection_state();
+        let Some(previous) = self.history.undo().cloned() else {
+            self.status_message = Some("Nothing to undo".to_string());
+            return;
         };
+        self.status_message = Some(format!(
+            "Undid: {}",
+            self.describe_change(&previous, &current)
+        ));
+        self.restore_selection(previous);
+    }
 
-        for entry in entries.filter_map(|e| e.ok()) {
-            let path = entry.path();
+    fn redo(&mut self) {
+        let current = self.selection_state();
+        let Some(next) = self.history.redo().cloned() else {
+            self.status_message = Some("Nothing to redo".to_string());
+            return;
+        };
+        self.status_message = Some(format!("Redid: {}", self.describe_change(&current, &next)));
+        self.restore_selection(next);
+    }
 
-            // Skip hidden files if not showing them
-            if !self.show_hidden && entry.file_name().to_string_lossy().starts_with('.') {
-                continue;
-            }
+    fn restore_selection(&mut self, state: SelectionState) {
+        self.selected_files = state.files;
+        self.line_ranges = state.line_ranges;
+        self.sync_selection_marks(None);
+    }
+
+    /// Describes the change from one selection to another for the status
+    /// bar, e.g. "selected src/main.rs" or "deselected 12 files".
+    fn describe_change(&self, before: &SelectionState, after: &SelectionState) -> String {
+        let name = |files: Vec<&PathBuf>| match files.as_slice() {
+            [file] => self.workspace.display_path(file).display().to_string(),
+            files => format!("{} files", files.len()),
+        };
+        let added: Vec<&PathBuf> = after.files.difference(&before.files).collect();
+        let removed: Vec<&PathBuf> = before.files.difference(&after.files).collect();
 
-            if path.is_file() {
-                files.push(path);
-            } else if path.is_dir() {
-                // Recursively get ALL files from subdirectories (whether expanded or not)
-                files.extend(self.get_all_files_in_directory(&path));
+        match (added.is_empty(), removed.is_empty()) {
+            (false, true) => format!("selected {}", name(added)),
+            (true, false) => format!("deselected {}", name(removed)),
+            (false, false) => format!("selected {} and deselected {}", name(added), name(removed)),
+            (true, true) => {
+                let changed: Vec<&PathBuf> = before
+                    .line_ranges
+                    .keys()
+                    .chain(after.line_ranges.keys())
+                    .filter(|file| before.line_ranges.get(*file) != after.line_ranges.get(*file))
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect();
+                format!("changed the exported lines of {}", name(changed))
             }
         }
+    }
 
-        files
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        match mouse.kind {
+            MouseEventKind::ScrollDown => self.scroll(SCROLL_LINES as isize),
+            MouseEventKind::ScrollUp => self.scroll(-(SCROLL_LINES as isize)),
+            MouseEventKind::Down(MouseButton::Left) => self.click(mouse.column, mouse.row),
+            _ => {}
+        }
     }
 
-    fn select_all_files(&mut self) {
-        // Select all visible files in the entire tree
-        for item in &self.items {
-            if !item.is_dir {
-                self.selected_files.insert(item.path.clone());
-            }
+    /// Returns the list shown in the main area and its number of rows.
+    fn active_list(&mut self) -> (&mut ListState, usize) {
+        match &mut self.search {
+            Some(search) => (&mut search.list_state, search.results.len()),
+            None => (&mut self.list_state, self.items.len()),
         }
-        self.refresh_items().unwrap_or(());
     }
 
-    fn deselect_all(&mut self) {
-        self.selected_files.clear();
-        self.refresh_items().unwrap_or(());
+    /// Returns the row at a screen position in the list and the column within
+    /// the row's text (after the cursor symbol), if the position is on a row.
+    fn row_at(&mut self, column: u16, row: u16) -> Option<(usize, Option<u16>)> {
+        let inner = self.list_area.inner(Margin::new(1, 1));
+        if !inner.contains(Position::new(column, row)) {
+            return None;
+        }
+
+        let (state, len) = self.active_list();
+        let index = state.offset() + usize::from(row - inner.y);
+        let text_column = (column - inner.x).checked_sub(HIGHLIGHT_WIDTH);
+        (index < len).then_some((index, text_column))
+    }
+
+    /// Moves the cursor to the clicked row. Clicking a file's checkbox
+    /// toggles it, and clicking a directory's arrow expands or collapses it.
+    fn click(&mut self, column: u16, row: u16) {
+        if self.search.as_ref().is_some_and(|search| search.editing) {
+            return;
+        }
+        let Some((index, text_column)) = self.row_at(column, row) else {
+            return;
+        };
+        self.active_list().0.select(Some(index));
+
+        if let Some(search) = &self.search {
+            let marker_width = self.theme.symbols.marker_width;
+            if text_column.is_some_and(|column| column < marker_width) {
+                let found = search.results[index].clone();
+                self.toggle_search_results(&[found]);
+            }
+            return;
+        }
+
+        // The checkbox or arrow follows the row's indentation
+        let item = &self.items[index];
+        let marker = (item.depth * 2) as u16;
+        let marker_end = marker + self.theme.symbols.marker_width;
+        if !text_column.is_some_and(|column| (marker..marker_end).contains(&column)) {
+            return;
+        }
+        if !item.is_dir {
+            self.toggle_selection();
+        } else if item.is_expanded {
+            self.collapse_current_directory();
+        } else {
+            self.expand_current_directory();
+        }
     }
 
-    fn move_selection_down(&mut self) {
-        let selected = self.list_state.selected().unwrap_or(0);
-        if selected < self.items.len().saturating_sub(1) {
-            self.list_state.select(Some(selected + 1));
+    /// Scrolls the list by `lines` rows, keeping the cursor on screen.
+    fn scroll(&mut self, lines: isize) {
+        let height = usize::from(self.list_area.height.saturating_sub(2));
+        let (state, len) = self.active_list();
+        if len == 0 || height == 0 {
+            return;
         }
+
+        let offset = state
+            .offset()
+            .saturating_add_signed(lines)
+            .min(len.saturating_sub(height));
+        *state.offset_mut() = offset;
+
+        // Rendering scrolls back to the cursor, so move it into view
+        let last_visible = (offset + height - 1).min(len - 1);
+        let selected = state.selected().unwrap_or(0).clamp(offset, last_visible);
+        state.select(Some(selected));
     }
 
-    fn move_selection_up(&mut self) {
-        let selected = self.list_state.selected().unwrap_or(0);
-        if selected > 0 {
-            self.list_state.select(Some(selected - 1));
+    /// Moves the cursor of the active list by `delta` rows, stopping at
+    /// the first and last row.
+    fn move_cursor(&mut self, delta: isize) {
+        let (state, len) = self.active_list();
+        if len == 0 {
+            return;
         }
+        let selected = state.selected().unwrap_or(0).saturating_add_signed(delta);
+        state.select(Some(selected.min(len - 1)));
+    }
+
+    /// Returns the number of rows visible in the list.
+    fn page_size(&self) -> isize {
+        self.list_area.height.saturating_sub(2).max(1) as isize
     }
 }
@@ -0,0 +1,94 @@
+//! # Undo History
+//!
+//! Keeps past and undone states of a value so changes can be undone and
+//! redone, e.g. the selection of the interactive selector.
+//!
+//! Instead of recording each operation, the owner hands [`History::record`]
+//! the current state after a change; states equal to the last recorded one
+//! are ignored. Operations only have to flag that they changed the value,
+//! including ones that finish later in the background, without knowing how
+//! to undo themselves.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use tree_txt::history::History;
+//!
+//! let mut history = History::new(vec!["main.rs"], 10);
+//! history.record(&vec!["main.rs", "lib.rs"]);
+//! history.record(&vec![]);
+//!
+//! assert_eq!(history.undo(), Some(&vec!["main.rs", "lib.rs"]));
+//! assert_eq!(history.undo(), Some(&vec!["main.rs"]));
+//! assert_eq!(history.undo(), None);
+//! assert_eq!(history.redo(), Some(&vec!["main.rs", "lib.rs"]));
+//!
+//! // A new change discards what could have been redone
+//! history.record(&vec!["lib.rs"]);
+//! assert_eq!(history.redo(), None);
+//! ```
+
+use std::collections::VecDeque;
+
+/// Undo and redo stacks around the current state.
+#[derive(Debug, Clone)]
+pub struct History<T> {
+    current: T,
+    /// Earlier states, oldest first, so the oldest can be dropped cheaply
+    undo: VecDeque<T>,
+    redo: Vec<T>,
+    /// Maximum number of states kept for undo
+    limit: usize,
+}
+
+impl<T: Clone + PartialEq> History<T> {
+    /// Starts a history at `state`, keeping up to `limit` earlier states.
+    pub fn new(state: T, limit: usize) -> Self {
+        Self {
+            current: state,
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit,
+        }
+    }
+
+    /// Makes `state` the current state, so the previous one can be restored
+    /// with [`undo`](Self::undo). Returns `false` and changes nothing if
+    /// `state` equals the current state.
+    pub fn record(&mut self, state: &T) -> bool {
+        if *state == self.current {
+            return false;
+        }
+
+        let previous = std::mem::replace(&mut self.current, state.clone());
+        self.undo.push_back(previous);
+        if self.undo.len() > self.limit {
+            self.undo.pop_front();
+        }
+        self.redo.clear();
+        true
+    }
+
+    /// Steps back to the previous state and returns it, or returns `None`
+    /// if there is nothing to undo.
+    pub fn undo(&mut self) -> Option<&T> {
+        let previous = self.undo.pop_back()?;
+        let undone = std::mem::replace(&mut self.current, previous);
+        self.redo.push(undone);
+        Some(&self.current)
+    }
+
+    /// Steps forward to the most recently undone state and returns it, or
+    /// returns `None` if there is nothing to redo.
+    pub fn redo(&mut self) -> Option<&T> {
+        let next = self.redo.pop()?;
+        let redone = std::mem::replace(&mut self.current, next);
+        self.undo.push_back(redone);
+        Some(&self.current)
+    }
+
+    /// Returns the current state.
+    pub fn current(&self) -> &T {
+        &self.current
+    }
+}
@@ -0,0 +1,523 @@
+//! # Key Bindings
+//!
+//! Maps key presses in the interactive selector to [`Action`]s.
+//!
+//! A [`Keymap`] starts from a [`Preset`] (`default`, `vim` or `emacs`) and can
+//! be customised in the `[keys]` table of the user configuration file (see
+//! [`user_config`](crate::user_config)), mapping action names to one key or a
+//! list of keys:
+//!
+//! ```toml
+//! [keys]
+//! preset = "vim"
+//! toggle_hidden = "ctrl+h"
+//! select_all = ["ctrl+a", "A"]
+//! ```
+//!
+//! Keys are written as an optional `ctrl+`, `alt+` or `shift+` prefix followed
+//! by a character or a key name such as `enter`, `space`, `esc`, `up`,
+//! `pagedown` or `f5`. A key bound in the configuration is removed from every
+//! other action, so rebinding never leaves two actions on one key. `Ctrl+C`
+//! (cancel) and `Ctrl+Z` (suspend) are reserved.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+//! use tree_txt::keymap::{Action, Keymap, Preset};
+//! use std::collections::BTreeMap;
+//!
+//! let mut overrides = BTreeMap::new();
+//! overrides.insert("toggle_hidden".to_string(), vec!["ctrl+h".to_string()]);
+//! let keymap = Keymap::new(Preset::Vim, &overrides)?;
+//!
+//! let ctrl_h = KeyEvent::new(KeyCode::Char('h'), KeyModifiers::CONTROL);
+//! assert_eq!(keymap.action_for(&ctrl_h), Some(Action::ToggleHidden));
+//!
+//! let h = KeyEvent::new(KeyCode::Char('h'), KeyModifiers::NONE);
+//! assert_eq!(keymap.action_for(&h), Some(Action::Collapse));
+//! assert_eq!(keymap.keys_label(Action::Collapse), "←/h");
+//! # Ok::<(), tree_txt::TreeTxtError>(())
+//! ```
+
+use crate::error::{Result, TreeTxtError};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+/// Something the user can do in the interactive selector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Confirm,
+    Toggle,
+    Expand,
+    Collapse,
+    Down,
+    Up,
+    First,
+    Last,
+    PageDown,
+    PageUp,
+    OutlineFile,
+    OutlineAll,
+    SelectAll,
+    DeselectAll,
+    Undo,
+    Redo,
+    ToggleHidden,
+    Reload,
+    Search,
+    SelectMatches,
+    MatchingLines,
+    Back,
+    AddImports,
+    Help,
+}
+
+impl Action {
+    /// Every action, in the order they are listed in the help overlay.
+    pub const ALL: [Action; 25] = [
+        Action::Down,
+        Action::Up,
+        Action::First,
+        Action::Last,
+        Action::PageDown,
+        Action::PageUp,
+        Action::Expand,
+        Action::Collapse,
+        Action::Toggle,
+        Action::SelectAll,
+        Action::DeselectAll,
+        Action::Undo,
+        Action::Redo,
+        Action::Search,
+        Action::SelectMatches,
+        Action::MatchingLines,
+        Action::Back,
+        Action::AddImports,
+        Action::OutlineFile,
+        Action::OutlineAll,
+        Action::ToggleHidden,
+        Action::Reload,
+        Action::Help,
+        Action::Confirm,
+        Action::Quit,
+    ];
+
+    /// Name used in the `[keys]` table of the user configuration.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Confirm => "confirm",
+            Action::Toggle => "toggle",
+            Action::Expand => "expand",
+            Action::Collapse => "collapse",
+            Action::Down => "down",
+            Action::Up => "up",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::PageDown => "page_down",
+            Action::PageUp => "page_up",
+            Action::OutlineFile => "outline_file",
+            Action::OutlineAll => "outline_all",
+            Action::SelectAll => "select_all",
+            Action::DeselectAll => "deselect_all",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::ToggleHidden => "toggle_hidden",
+            Action::Reload => "reload",
+            Action::Search => "search",
+            Action::SelectMatches => "select_matches",
+            Action::MatchingLines => "matching_lines",
+            Action::Back => "back",
+            Action::AddImports => "add_imports",
+            Action::Help => "help",
+        }
+    }
+
+    /// Parses a name returned by [`name`](Self::name).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    /// Short label for the help bar.
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Confirm => "confirm",
+            Action::Toggle => "select/select dir",
+            Action::Expand => "expand",
+            Action::Collapse => "collapse",
+            Action::Down => "down",
+            Action::Up => "up",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::PageDown => "page down",
+            Action::PageUp => "page up",
+            Action::OutlineFile => "outline file",
+            Action::OutlineAll => "outline all",
+            Action::SelectAll => "select all",
+            Action::DeselectAll => "clear",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::ToggleHidden => "toggle hidden",
+            Action::Reload => "reload",
+            Action::Search => "search contents",
+            Action::SelectMatches => "select all matches",
+            Action::MatchingLines => "matching lines only",
+            Action::Back => "back to tree",
+            Action::AddImports => "add imports",
+            Action::Help => "help",
+        }
+    }
+
+    /// Full description for the help overlay.
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit without exporting",
+            Action::Confirm => "Confirm the selection and export it",
+            Action::Toggle => "Select or deselect the file or directory under the cursor",
+            Action::Expand => "Expand the directory under the cursor",
+            Action::Collapse => "Collapse the directory under the cursor",
+            Action::Down => "Move the cursor down",
+            Action::Up => "Move the cursor up",
+            Action::First => "Move the cursor to the first row",
+            Action::Last => "Move the cursor to the last row",
+            Action::PageDown => "Move the cursor down one page",
+            Action::PageUp => "Move the cursor up one page",
+            Action::OutlineFile => "Export the file under the cursor as an outline",
+            Action::OutlineAll => "Toggle outline mode for all files",
+            Action::SelectAll => "Select all visible files",
+            Action::DeselectAll => "Deselect all files",
+            Action::Undo => "Undo the last change to the selection",
+            Action::Redo => "Redo the last undone change to the selection",
+            Action::ToggleHidden => "Show or hide hidden files",
+            Action::Reload => "Reload the directory tree from disk",
+            Action::Search => "Search file contents",
+            Action::SelectMatches => "In search results, select or deselect all matching files",
+            Action::MatchingLines => {
+                "In search results, export only the matching lines of selected files"
+            }
+            Action::Back => "Leave the search results and return to the tree",
+            Action::AddImports => "Add the files imported by the selected files",
+            Action::Help => "Show or hide this help",
+        }
+    }
+}
+
+/// A key press, with the modifiers held down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    /// Cancels the selector without exporting. Cannot be rebound.
+    pub const CANCEL: Self = Self {
+        code: KeyCode::Char('c'),
+        modifiers: KeyModifiers::CONTROL,
+    };
+
+    /// Suspends the selector to the shell. Cannot be rebound.
+    pub const SUSPEND: Self = Self {
+        code: KeyCode::Char('z'),
+        modifiers: KeyModifiers::CONTROL,
+    };
+
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Shift is already part of a typed character ('O', '?', '<')
+        let modifiers = match code {
+            KeyCode::Char(_) => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        // Terminals report Ctrl+Shift+A as Ctrl+A
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => {
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            code => code,
+        };
+        Self { code, modifiers }
+    }
+
+    /// Returns the binding matching a key event.
+    pub fn from_event(event: &KeyEvent) -> Self {
+        Self::new(event.code, event.modifiers)
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        while let Some((prefix, key)) = rest.split_once('+')
+            && !key.is_empty()
+        {
+            modifiers |= match prefix.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier '{prefix}' in key '{s}'")),
+            };
+            rest = key;
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "space" => KeyCode::Char(' '),
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "esc" | "escape" => KeyCode::Esc,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "delete" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(number @ 1..=24) => KeyCode::F(number),
+                    _ => return Err(format!("unknown key '{s}'")),
+                },
+            },
+        };
+
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => {
+                write!(f, "{}", c.to_ascii_uppercase())
+            }
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::Delete => write!(f, "Del"),
+            KeyCode::Insert => write!(f, "Ins"),
+            KeyCode::F(number) => write!(f, "F{number}"),
+            code => write!(f, "{code:?}"),
+        }
+    }
+}
+
+/// Built-in sets of key bindings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Preset {
+    /// Arrow keys plus `h`/`j`/`k`/`l`
+    #[default]
+    Default,
+    /// Vim-style movement: `g`/`G`, `Ctrl+D`/`Ctrl+U` and `Ctrl+F`/`Ctrl+B`
+    Vim,
+    /// Emacs-style movement: `Ctrl+N`/`Ctrl+P`, `Ctrl+F`/`Ctrl+B` and `Ctrl+S`
+    Emacs,
+}
+
+impl Preset {
+    /// Names accepted in the user configuration.
+    pub const NAMES: [&'static str; 3] = ["default", "vim", "emacs"];
+
+    /// Parses a name from [`NAMES`](Self::NAMES).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self::Default),
+            "vim" => Some(Self::Vim),
+            "emacs" => Some(Self::Emacs),
+            _ => None,
+        }
+    }
+
+    /// Returns the keys of `action` in this preset.
+    fn keys(self, action: Action) -> &'static [&'static str] {
+        let preset = match (self, action) {
+            (Preset::Vim, Action::First) => Some(&["home", "g"][..]),
+            (Preset::Vim, Action::Last) => Some(&["end", "G"][..]),
+            (Preset::Vim, Action::PageDown) => Some(&["pagedown", "ctrl+d", "ctrl+f"][..]),
+            (Preset::Vim, Action::PageUp) => Some(&["pageup", "ctrl+u", "ctrl+b"][..]),
+            (Preset::Vim, Action::DeselectAll) => Some(&["D"][..]),
+            (Preset::Emacs, Action::Down) => Some(&["down", "ctrl+n"][..]),
+            (Preset::Emacs, Action::Up) => Some(&["up", "ctrl+p"][..]),
+            (Preset::Emacs, Action::Expand) => Some(&["right", "ctrl+f"][..]),
+            (Preset::Emacs, Action::Collapse) => Some(&["left", "ctrl+b"][..]),
+            (Preset::Emacs, Action::First) => Some(&["home", "alt+<"][..]),
+            (Preset::Emacs, Action::Last) => Some(&["end", "alt+>"][..]),
+            (Preset::Emacs, Action::PageDown) => Some(&["pagedown", "ctrl+v"][..]),
+            (Preset::Emacs, Action::PageUp) => Some(&["pageup", "alt+v"][..]),
+            (Preset::Emacs, Action::Search) => Some(&["/", "ctrl+s"][..]),
+            (Preset::Emacs, Action::Quit) => Some(&["q", "ctrl+g"][..]),
+            _ => None,
+        };
+        preset.unwrap_or(match action {
+            Action::Quit => &["q"],
+            Action::Confirm => &["enter"],
+            Action::Toggle => &["space"],
+            Action::Expand => &["right", "l"],
+            Action::Collapse => &["left", "h"],
+            Action::Down => &["down", "j"],
+            Action::Up => &["up", "k"],
+            Action::First => &["home"],
+            Action::Last => &["end"],
+            Action::PageDown => &["pagedown"],
+            Action::PageUp => &["pageup"],
+            Action::OutlineFile => &["o"],
+            Action::OutlineAll => &["O"],
+            Action::SelectAll => &["ctrl+a"],
+            Action::DeselectAll => &["ctrl+d"],
+            Action::Undo => &["u"],
+            Action::Redo => &["ctrl+r"],
+            Action::ToggleHidden => &["."],
+            Action::Reload => &["f5"],
+            Action::Search => &["/"],
+            Action::SelectMatches => &["a"],
+            Action::MatchingLines => &["x"],
+            Action::Back => &["esc"],
+            Action::AddImports => &["d"],
+            Action::Help => &["?"],
+        })
+    }
+}
+
+/// The active key bindings of the interactive selector.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<KeyBinding>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(Preset::Default, &BTreeMap::new()).expect("built-in bindings are valid")
+    }
+}
+
+impl Keymap {
+    /// Builds the bindings of `preset`, replacing the keys of every action
+    /// named in `overrides`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TreeTxtError::ConfigError`] for an unknown action or key,
+    /// and for the reserved keys [`KeyBinding::CANCEL`] and
+    /// [`KeyBinding::SUSPEND`].
+    pub fn new(preset: Preset, overrides: &BTreeMap<String, Vec<String>>) -> Result<Self> {
+        let parse = |action: Action, keys: &[&str]| -> Result<Vec<KeyBinding>> {
+            keys.iter()
+                .map(|key| {
+                    key.parse().map_err(|err| {
+                        TreeTxtError::ConfigError(format!(
+                            "Invalid key binding for '{}': {err}",
+                            action.name()
+                        ))
+                    })
+                })
+                .collect()
+        };
+
+        let mut bindings = Vec::with_capacity(Action::ALL.len());
+        for action in Action::ALL {
+            bindings.push((action, parse(action, preset.keys(action))?));
+        }
+
+        for (name, keys) in overrides {
+            let action = Action::from_name(name).ok_or_else(|| {
+                TreeTxtError::ConfigError(format!("Unknown action '{name}' in [keys]"))
+            })?;
+            let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+            let keys = parse(action, &keys)?;
+
+            for (other, other_keys) in &mut bindings {
+                if *other == action {
+                    other_keys.clone_from(&keys);
+                } else if !overrides.contains_key(other.name()) {
+                    other_keys.retain(|key| !keys.contains(key));
+                }
+            }
+        }
+
+        // Overrides may still clash with each other
+        let mut bound: HashMap<KeyBinding, Action> = HashMap::new();
+        for (action, keys) in &bindings {
+            for key in keys {
+                if [KeyBinding::CANCEL, KeyBinding::SUSPEND].contains(key) {
+                    return Err(TreeTxtError::ConfigError(format!(
+                        "Key '{key}' is reserved and cannot be bound to '{}'",
+                        action.name()
+                    )));
+                }
+                if let Some(other) = bound.insert(*key, *action) {
+                    return Err(TreeTxtError::ConfigError(format!(
+                        "Key '{key}' is bound to both '{}' and '{}'",
+                        other.name(),
+                        action.name()
+                    )));
+                }
+            }
+        }
+
+        Ok(Self { bindings })
+    }
+
+    /// Returns the action bound to a key press, if any.
+    pub fn action_for(&self, event: &KeyEvent) -> Option<Action> {
+        let pressed = KeyBinding::from_event(event);
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.contains(&pressed))
+            .map(|(action, _)| *action)
+    }
+
+    /// Returns the keys bound to `action`.
+    pub fn keys(&self, action: Action) -> &[KeyBinding] {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == action)
+            .map_or(&[], |(_, keys)| keys)
+    }
+
+    /// Returns the keys bound to `action` joined with `/`, e.g. `↓/j`.
+    pub fn keys_label(&self, action: Action) -> String {
+        let keys: Vec<String> = self.keys(action).iter().map(ToString::to_string).collect();
+        keys.join("/")
+    }
+
+    /// Returns the first key bound to `action` for the help bar, or `None`
+    /// if the action is unbound.
+    pub fn primary_key(&self, action: Action) -> Option<KeyBinding> {
+        self.keys(action).first().copied()
+    }
+}
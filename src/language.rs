@@ -0,0 +1,234 @@
+//! # Language Detection
+//!
+//! Maps file paths to the programming or markup language they contain, based
+//! on well-known file names and extensions.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use tree_txt::language::Language;
+//! use std::path::Path;
+//!
+//! assert_eq!(Language::detect(Path::new("src/main.rs")), Language::Rust);
+//! assert_eq!(Language::detect(Path::new("Makefile")), Language::Makefile);
+//! assert_eq!(Language::detect(Path::new("notes.xyz")), Language::Unknown);
+//! ```
+
+use std::path::Path;
+
+/// Languages recognised by Tree-TXT.
+///
+/// Detection is purely name-based; file contents are never inspected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Go,
+    C,
+    Cpp,
+    CSharp,
+    Java,
+    Kotlin,
+    Swift,
+    Ruby,
+    Php,
+    Shell,
+    Lua,
+    Sql,
+    Html,
+    Css,
+    Markdown,
+    Json,
+    Toml,
+    Yaml,
+    Xml,
+    Makefile,
+    Dockerfile,
+    Text,
+    Unknown,
+}
+
+impl Language {
+    /// Detects the language of a file from its name or extension.
+    pub fn detect(path: &Path) -> Self {
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        match file_name.as_str() {
+            "makefile" | "gnumakefile" => return Self::Makefile,
+            "dockerfile" | "containerfile" => return Self::Dockerfile,
+            "cargo.lock" => return Self::Toml,
+            _ => {}
+        }
+
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        match extension.as_str() {
+            "rs" => Self::Rust,
+            "py" | "pyi" => Self::Python,
+            "js" | "jsx" | "mjs" | "cjs" => Self::JavaScript,
+            "ts" | "tsx" | "mts" | "cts" => Self::TypeScript,
+            "go" => Self::Go,
+            "c" | "h" => Self::C,
+            "cc" | "cpp" | "cxx" | "hh" | "hpp" | "hxx" => Self::Cpp,
+            "cs" => Self::CSharp,
+            "java" => Self::Java,
+            "kt" | "kts" => Self::Kotlin,
+            "swift" => Self::Swift,
+            "rb" => Self::Ruby,
+            "php" => Self::Php,
+            "sh" | "bash" | "zsh" | "fish" => Self::Shell,
+            "lua" => Self::Lua,
+            "sql" => Self::Sql,
+            "html" | "htm" => Self::Html,
+            "css" | "scss" | "sass" | "less" => Self::Css,
+            "md" | "markdown" => Self::Markdown,
+            "json" => Self::Json,
+            "toml" => Self::Toml,
+            "yml" | "yaml" => Self::Yaml,
+            "xml" | "svg" => Self::Xml,
+            "mk" => Self::Makefile,
+            "txt" | "text" => Self::Text,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Returns the human-readable name of the language.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Rust => "Rust",
+            Self::Python => "Python",
+            Self::JavaScript => "JavaScript",
+            Self::TypeScript => "TypeScript",
+            Self::Go => "Go",
+            Self::C => "C",
+            Self::Cpp => "C++",
+            Self::CSharp => "C#",
+            Self::Java => "Java",
+            Self::Kotlin => "Kotlin",
+            Self::Swift => "Swift",
+            Self::Ruby => "Ruby",
+            Self::Php => "PHP",
+            Self::Shell => "Shell",
+            Self::Lua => "Lua",
+            Self::Sql => "SQL",
+            Self::Html => "HTML",
+            Self::Css => "CSS",
+            Self::Markdown => "Markdown",
+            Self::Json => "JSON",
+            Self::Toml => "TOML",
+            Self::Yaml => "YAML",
+            Self::Xml => "XML",
+            Self::Makefile => "Makefile",
+            Self::Dockerfile => "Dockerfile",
+            Self::Text => "Text",
+            Self::Unknown => "Unknown",
+        }
+    }
+}
+
+/// Describes how comments and string literals are written in a language.
+///
+/// Used by the content transforms to strip comments without touching the
+/// contents of string literals.
+#[derive(Debug, Clone, Copy)]
+pub struct CommentSyntax {
+    /// Line comment markers, e.g. `//` or `#`
+    pub line: &'static [&'static str],
+    /// Block comment delimiters, e.g. `("/*", "*/")`
+    pub block: &'static [(&'static str, &'static str)],
+    /// Whether block comments nest (as in Rust and Swift)
+    pub nested_blocks: bool,
+    /// String literal delimiters, longest first
+    pub strings: &'static [&'static str],
+    /// Whether a line comment marker must start a line or follow whitespace
+    pub line_marker_needs_space: bool,
+    /// Whether the language has Rust-style char literals, lifetimes and raw strings
+    pub rust_literals: bool,
+}
+
+const C_STYLE: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: &[("/*", "*/")],
+    nested_blocks: false,
+    strings: &["\"", "'"],
+    line_marker_needs_space: false,
+    rust_literals: false,
+};
+
+const HASH_STYLE: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    block: &[],
+    nested_blocks: false,
+    strings: &["\"", "'"],
+    line_marker_needs_space: false,
+    rust_literals: false,
+};
+
+impl Language {
+    /// Returns the comment syntax of the language, or `None` if comments
+    /// cannot be stripped safely (e.g. JSON or plain text).
+    pub fn comment_syntax(&self) -> Option<CommentSyntax> {
+        let syntax = match self {
+            Self::Rust => CommentSyntax {
+                nested_blocks: true,
+                strings: &["\""],
+                rust_literals: true,
+                ..C_STYLE
+            },
+            Self::Swift | Self::Kotlin => CommentSyntax {
+                nested_blocks: true,
+                strings: &["\"\"\"", "\""],
+                ..C_STYLE
+            },
+            Self::JavaScript | Self::TypeScript | Self::Go => CommentSyntax {
+                strings: &["\"", "'", "`"],
+                ..C_STYLE
+            },
+            Self::C | Self::Cpp | Self::CSharp | Self::Java => C_STYLE,
+            Self::Php => CommentSyntax {
+                line: &["//", "#"],
+                ..C_STYLE
+            },
+            Self::Css => CommentSyntax {
+                line: &[],
+                ..C_STYLE
+            },
+            Self::Python => CommentSyntax {
+                strings: &["\"\"\"", "'''", "\"", "'"],
+                ..HASH_STYLE
+            },
+            Self::Ruby | Self::Toml => HASH_STYLE,
+            Self::Shell | Self::Yaml | Self::Makefile | Self::Dockerfile => CommentSyntax {
+                line_marker_needs_space: true,
+                ..HASH_STYLE
+            },
+            Self::Lua => CommentSyntax {
+                line: &["--"],
+                block: &[("--[[", "]]")],
+                ..C_STYLE
+            },
+            Self::Sql => CommentSyntax {
+                line: &["--"],
+                strings: &["'"],
+                ..C_STYLE
+            },
+            Self::Html | Self::Xml | Self::Markdown => CommentSyntax {
+                line: &[],
+                block: &[("<!--", "-->")],
+                strings: &[],
+                ..C_STYLE
+            },
+            Self::Json | Self::Text | Self::Unknown => return None,
+        };
+
+        Some(syntax)
+    }
+}
@@ -19,14 +19,15 @@
 //! let generator = OutputGenerator::new();
 //! let config = OutputFormat::default();
 //! let files = vec![Path::new("src/main.rs").to_path_buf()];
+//! let output = std::env::temp_dir().join("tree-txt-doc-output.txt");
 //!
 //! generator.generate_with_config(
 //!     Path::new("."),
 //!     &files,
-//!     "output.txt",
+//!     output.to_str().unwrap(),
 //!     &config,
 //! )?;
-//! # Ok::<(), anyhow::Error>(())
+//! # Ok::<(), tree_txt::TreeTxtError>(())
 //! ```
 //!
 //! ## Features
@@ -46,15 +47,36 @@
 //! - Memory-safe Rust implementation with proper error handling
 //! - No arbitrary code execution or unsafe operations
 
+pub mod chunker;
+pub mod clipboard;
 pub mod config;
+pub mod dependencies;
+pub mod encoding;
 pub mod error;
 pub mod file_selector;
+pub mod history;
+pub mod keymap;
+pub mod language;
+pub mod line_range;
+pub mod metadata;
+pub mod ordering;
+pub mod outline;
 pub mod output_generator;
+pub mod project;
+pub mod redaction;
+pub mod search;
 pub mod state_manager;
+pub mod terminal;
+pub mod theme;
+pub mod tokens;
+pub mod transform;
+pub mod tree_model;
+pub mod user_config;
+pub mod watch;
 
 // Re-export main types for convenience
 pub use config::{Config, OutputFormat};
-pub use error::{Result, TreeTxtError};
+pub use error::{ErrorPolicy, Result, TreeTxtError};
 pub use file_selector::{FileItem, FileSelector};
-pub use output_generator::OutputGenerator;
+pub use output_generator::{ExportReport, OutputGenerator};
 pub use state_manager::{GlobalState, ProjectState, StateManager};
@@ -0,0 +1,163 @@
+//! # Line Ranges
+//!
+//! Restricts the export of a file to some of its lines, e.g. `src/lib.rs:120-240`.
+//!
+//! A file entry in a configuration file or on the command line may end with
+//! `:<ranges>`, where `<ranges>` is a comma-separated list of `N-M` (lines N to
+//! M), `N-` (line N to the end) or `N` (line N only). Line numbers are 1-based
+//! and inclusive, and excerpts keep the file's original line numbers.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use tree_txt::line_range::{LineRange, split_file_spec};
+//! use std::path::{Path, PathBuf};
+//!
+//! let (path, ranges) = split_file_spec(Path::new("src/lib.rs:120-240,300-"));
+//! assert_eq!(path, PathBuf::from("src/lib.rs"));
+//! assert_eq!(ranges, vec![LineRange::new(120, Some(240)), LineRange::new(300, None)]);
+//!
+//! let (path, ranges) = split_file_spec(Path::new("src/main.rs"));
+//! assert_eq!(path, PathBuf::from("src/main.rs"));
+//! assert!(ranges.is_empty());
+//! ```
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// An inclusive, 1-based range of lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineRange {
+    /// First line of the range
+    pub start: usize,
+    /// Last line of the range, or `None` for the end of the file
+    pub end: Option<usize>,
+}
+
+impl LineRange {
+    /// Creates a range from `start` to `end` (inclusive).
+    pub fn new(start: usize, end: Option<usize>) -> Self {
+        Self { start, end }
+    }
+
+    /// Returns the last line of the range in a file of `line_count` lines.
+    pub fn end_within(&self, line_count: usize) -> usize {
+        self.end.map_or(line_count, |end| end.min(line_count))
+    }
+}
+
+impl fmt::Display for LineRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.end {
+            Some(end) if end == self.start => write!(f, "{}", self.start),
+            Some(end) => write!(f, "{}-{end}", self.start),
+            None => write!(f, "{}-", self.start),
+        }
+    }
+}
+
+impl FromStr for LineRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |number: &str| {
+            number
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|&line| line > 0)
+                .ok_or_else(|| format!("invalid line number '{number}' in range '{s}'"))
+        };
+
+        let range = match s.split_once('-') {
+            Some((start, "")) => Self::new(parse(start)?, None),
+            Some((start, end)) => Self::new(parse(start)?, Some(parse(end)?)),
+            None => {
+                let line = parse(s)?;
+                Self::new(line, Some(line))
+            }
+        };
+
+        if range.end.is_some_and(|end| end < range.start) {
+            return Err(format!("line range '{s}' ends before it starts"));
+        }
+        Ok(range)
+    }
+}
+
+impl Serialize for LineRange {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for LineRange {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Parses a comma-separated list of ranges such as `1-20,40-`.
+pub fn parse_ranges(s: &str) -> Result<Vec<LineRange>, String> {
+    s.split(',').map(str::parse).collect()
+}
+
+/// Splits a `path:ranges` file entry into the path and its line ranges.
+///
+/// Entries without a valid range suffix, and paths that exist as written
+/// (such as a file whose name contains a colon), are returned unchanged with
+/// no ranges.
+pub fn split_file_spec(spec: &Path) -> (PathBuf, Vec<LineRange>) {
+    let whole = || (spec.to_path_buf(), Vec::new());
+    if spec.exists() {
+        return whole();
+    }
+
+    let Some((path, ranges)) = spec.to_str().and_then(|s| s.rsplit_once(':')) else {
+        return whole();
+    };
+    match parse_ranges(ranges) {
+        Ok(ranges) if !path.is_empty() => (PathBuf::from(path), ranges),
+        _ => whole(),
+    }
+}
+
+/// Sorts `ranges` and merges the ones that overlap or touch.
+///
+/// # Examples
+///
+/// ```rust
+/// use tree_txt::line_range::{LineRange, merge};
+///
+/// let merged = merge(&[
+///     LineRange::new(30, Some(40)),
+///     LineRange::new(1, Some(10)),
+///     LineRange::new(11, Some(12)),
+///     LineRange::new(35, None),
+/// ]);
+/// assert_eq!(merged, vec![LineRange::new(1, Some(12)), LineRange::new(30, None)]);
+/// ```
+pub fn merge(ranges: &[LineRange]) -> Vec<LineRange> {
+    let mut sorted = ranges.to_vec();
+    sorted.sort();
+
+    let mut merged: Vec<LineRange> = Vec::with_capacity(sorted.len());
+    for range in sorted {
+        if let Some(last) = merged.last_mut()
+            && last.end.is_none_or(|end| range.start <= end + 1)
+        {
+            last.end = match (last.end, range.end) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                _ => None,
+            };
+            continue;
+        }
+        merged.push(range);
+    }
+
+    merged
+}
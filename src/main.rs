@@ -5,40 +5,111 @@
 //! This binary provides a command-line interface for Tree-TXT functionality,
 //! including interactive file selection and batch processing via configuration files.
 
-use anyhow::Result;
-use clap::{Arg, Command};
+use anyhow::{Context, Result};
+use clap::{Arg, ArgAction, ArgGroup, ArgMatches, Command};
+use clap_complete::Shell;
+use std::collections::BTreeMap;
 use std::env;
-use std::path::Path;
+use std::fmt;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Mutex;
 
-mod config;
-mod error;
-mod file_selector;
-mod output_generator;
-mod state_manager;
+use tree_txt::clipboard::{self, Backend};
+use tree_txt::dependencies;
+use tree_txt::line_range::LineRange;
+use tree_txt::metadata::format_size;
+use tree_txt::ordering::SortOrder;
+use tree_txt::output_generator::Progress;
+use tree_txt::project::{Workspace, find_project_root};
+use tree_txt::redaction::is_sensitive_filename;
+use tree_txt::search::{self, FileMatch, Query};
+use tree_txt::tokens::{SizeUnit, estimate_tokens};
+use tree_txt::user_config::UserConfig;
+use tree_txt::watch::{Changes, Watcher};
+use tree_txt::{
+    Config, ErrorPolicy, FileSelector, OutputFormat, OutputGenerator, StateManager, TreeTxtError,
+};
 
-use config::Config;
-use error::TreeTxtError;
-use file_selector::FileSelector;
-use output_generator::OutputGenerator;
-use state_manager::StateManager;
+/// Default file written by `tree-txt init`.
+const DEFAULT_CONFIG_FILE: &str = "tree-txt.toml";
+
+/// Exports of at least this many files show a progress bar on stderr.
+const PROGRESS_MIN_FILES: usize = 200;
+
+/// Width of the progress bar, in characters.
+const PROGRESS_BAR_WIDTH: usize = 30;
 
 /// Main entry point for the Tree-TXT CLI application.
 ///
-/// Parses command-line arguments and either launches the interactive file selector
-/// or processes files according to a configuration file.
+/// Parses command-line arguments and dispatches to a subcommand. Without a
+/// subcommand, either launches the interactive file selector or processes
+/// files according to a configuration file.
 ///
 /// # Examples
 ///
 /// Run interactively:
 /// ```bash
 /// tree-txt
+/// tree-txt select
 /// ```
 ///
 /// Use configuration file:
 /// ```bash  
 /// tree-txt -c config.toml -o output.txt
+/// tree-txt export -c config.toml -o output.txt
 /// ```
 ///
+/// # Exit Codes
+///
+/// Failures are reported on stderr and mapped to the exit code of the
+/// underlying [`TreeTxtError`] (see [`TreeTxtError::exit_code`]):
+/// - 1: other I/O errors
+/// - 2: invalid command-line usage
+/// - 3: invalid configuration file, redaction pattern or search query
+/// - 4: path not found or not usable
+/// - 5: no files selected
+/// - 6: permission denied
+/// - 7: a file or directory could not be read
+/// - 8: the output file could not be written
+/// - 9: the state file could not be read or written
+/// - 10: the terminal could not be used
+/// - 11: no clipboard available
+/// - 130: the interactive selector was cancelled with Ctrl+C, SIGINT or SIGTERM
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {}", error_message(&error));
+            ExitCode::from(exit_code(&error))
+        }
+    }
+}
+
+/// Formats an error and its causes on one line.
+fn error_message(error: &anyhow::Error) -> String {
+    let mut parts = Vec::new();
+    for cause in error.chain() {
+        parts.push(cause.to_string());
+        // TreeTxtError messages already include their source
+        if cause.is::<TreeTxtError>() {
+            break;
+        }
+    }
+    parts.join(": ")
+}
+
+/// Returns the exit code of the first [`TreeTxtError`] in the error chain, or 1.
+fn exit_code(error: &anyhow::Error) -> u8 {
+    error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<TreeTxtError>())
+        .map_or(1, TreeTxtError::exit_code)
+}
+
+/// Parses the command line and runs the selected command.
+///
 /// # Errors
 ///
 /// Returns an error if:
@@ -47,117 +118,598 @@ use state_manager::StateManager;
 /// - No files are selected for export
 /// - Output directory is not writable
 /// - File generation fails
-fn main() -> Result<()> {
-    let matches = Command::new("tree-txt")
+fn run() -> Result<()> {
+    let matches = build_cli().get_matches();
+
+    match matches.subcommand() {
+        Some(("select", sub_matches)) => {
+            let workspace = workspace(sub_matches)?;
+            let (files, format) = select_interactively(&workspace)?;
+            export_selection(sub_matches, &workspace, &files, format, None)
+        }
+        Some(("export", sub_matches)) => {
+            let workspace = workspace(sub_matches)?;
+            let (files, format) = select_non_interactively(sub_matches, &workspace, true)?;
+            let reselect = || select_non_interactively(sub_matches, &workspace, false);
+            export_selection(sub_matches, &workspace, &files, format, Some(&reselect))
+        }
+        Some(("state", sub_matches)) => run_state(sub_matches),
+        Some(("init", sub_matches)) => run_init(sub_matches),
+        Some(("completions", sub_matches)) => {
+            let shell = *sub_matches
+                .get_one::<Shell>("shell")
+                .expect("shell is a required argument");
+            clap_complete::generate(shell, &mut build_cli(), "tree-txt", &mut io::stdout());
+            Ok(())
+        }
+        _ => {
+            let workspace = workspace(&matches)?;
+            match matches.get_one::<String>("config") {
+                Some(config_file) => {
+                    let (files, format) = load_config_selection(config_file)?;
+                    let reselect = || load_config_selection(config_file);
+                    export_selection(&matches, &workspace, &files, format, Some(&reselect))
+                }
+                None => {
+                    let (files, format) = select_interactively(&workspace)?;
+                    export_selection(&matches, &workspace, &files, format, None)
+                }
+            }
+        }
+    }
+}
+
+/// Builds the command-line interface, shared by argument parsing and shell completions.
+fn build_cli() -> Command {
+    Command::new("tree-txt")
         .version("0.1.0")
         .author("Tree-TXT")
         .about("Generate pretty-printed codebase text files from selected files")
-        .arg(
-            Arg::new("config")
-                .short('c')
-                .long("config")
-                .value_name("FILE")
-                .help("Use configuration file instead of interactive selection"),
+        .args_conflicts_with_subcommands(true)
+        .arg(config_arg().help("Use configuration file instead of interactive selection"))
+        .args(root_args())
+        .args(output_args())
+        .subcommand(
+            Command::new("select")
+                .about("Select files in the interactive interface and export them")
+                .args(root_args())
+                .args(output_args()),
         )
-        .arg(
-            Arg::new("output")
-                .short('o')
-                .long("output")
-                .value_name("FILE")
-                .help("Output file name (default: codebase.txt)"),
+        .subcommand(
+            Command::new("export")
+                .about("Export files without the interactive interface")
+                .arg(config_arg().help("Export the files listed in a configuration file"))
+                .arg(
+                    Arg::new("file")
+                        .short('f')
+                        .long("file")
+                        .value_name("PATH")
+                        .action(ArgAction::Append)
+                        .help("Add a file to the export, optionally limited to lines, e.g. src/lib.rs:120-240 (repeatable)"),
+                )
+                .arg(
+                    Arg::new("saved")
+                        .long("saved")
+                        .action(ArgAction::SetTrue)
+                        .help("Export the selections saved for this project"),
+                )
+                .arg(
+                    Arg::new("grep")
+                        .short('g')
+                        .long("grep")
+                        .value_name("PATTERN")
+                        .help("Add every file whose contents match a regular expression (respects .gitignore)"),
+                )
+                .arg(
+                    Arg::new("fixed_strings")
+                        .short('F')
+                        .long("fixed-strings")
+                        .action(ArgAction::SetTrue)
+                        .requires("grep")
+                        .help("Treat the --grep pattern as plain text"),
+                )
+                .arg(
+                    Arg::new("ignore_case")
+                        .short('i')
+                        .long("ignore-case")
+                        .action(ArgAction::SetTrue)
+                        .requires("grep")
+                        .help("Match the --grep pattern case-insensitively"),
+                )
+                .arg(
+                    Arg::new("context")
+                        .long("context")
+                        .value_name("LINES")
+                        .value_parser(clap::value_parser!(usize))
+                        .requires("grep")
+                        .help("Export only the lines matching --grep, with LINES lines of context around them"),
+                )
+                .group(
+                    ArgGroup::new("source")
+                        .args(["config", "file", "saved", "grep"])
+                        .multiple(true)
+                        .required(true),
+                )
+                .args(root_args())
+                .args(output_args()),
         )
-        .arg(
-            Arg::new("line_numbers")
-                .short('l')
-                .long("line-numbers")
-                .action(clap::ArgAction::SetTrue)
-                .help("Include line numbers in file contents"),
+        .subcommand(
+            Command::new("state")
+                .about("Manage saved selections")
+                .subcommand_required(true)
+                .args(root_args().into_iter().filter(|arg| arg.get_id() != "path"))
+                .subcommand(
+                    Command::new("show").about("Show the selections saved for this project"),
+                )
+                .subcommand(
+                    Command::new("clear").about("Forget the selections saved for this project"),
+                )
+                .subcommand(Command::new("list").about("List all projects with saved selections")),
         )
-        .arg(
-            Arg::new("no_tree")
-                .long("no-tree")
-                .action(clap::ArgAction::SetTrue)
-                .help("Skip directory tree generation"),
+        .subcommand(
+            Command::new("init")
+                .about("Create a starter configuration file")
+                .arg(
+                    Arg::new("path")
+                        .value_name("FILE")
+                        .default_value(DEFAULT_CONFIG_FILE)
+                        .help("Configuration file to create"),
+                )
+                .arg(
+                    Arg::new("from_saved")
+                        .long("from-saved")
+                        .action(ArgAction::SetTrue)
+                        .help("List the selections saved for this project in the new file"),
+                )
+                .arg(
+                    Arg::new("force")
+                        .long("force")
+                        .action(ArgAction::SetTrue)
+                        .help("Overwrite the file if it already exists"),
+                ),
         )
-        .arg(
-            Arg::new("no_content")
-                .long("no-content")
-                .action(clap::ArgAction::SetTrue)
-                .help("Only show file list, not contents"),
+        .subcommand(
+            Command::new("completions")
+                .about("Generate shell completions")
+                .arg(
+                    Arg::new("shell")
+                        .value_name("SHELL")
+                        .required(true)
+                        .value_parser(clap::value_parser!(Shell))
+                        .help("Shell to generate completions for (bash, zsh, fish, ...)"),
+                ),
         )
-        .get_matches();
+}
 
-    let current_dir = env::current_dir().map_err(|_| {
-        anyhow::anyhow!(
-            "Failed to get current directory. Please ensure you're in a valid directory."
-        )
-    })?;
+fn config_arg() -> Arg {
+    Arg::new("config")
+        .short('c')
+        .long("config")
+        .value_name("FILE")
+}
 
-    // Validate that current directory exists and is readable
-    if !current_dir.exists() {
-        return Err(anyhow::anyhow!("Current directory does not exist"));
-    }
+/// Options choosing the project root directories.
+fn root_args() -> Vec<Arg> {
+    vec![
+        Arg::new("path")
+            .value_name("PATH")
+            .num_args(1..)
+            .action(ArgAction::Append)
+            .help("Project root directories (default: current directory); several roots are exported together"),
+        Arg::new("root")
+            .long("root")
+            .value_name("DIR")
+            .action(ArgAction::Append)
+            .help("Project root directory (same as PATH, repeatable)"),
+        Arg::new("detect_root")
+            .long("detect-root")
+            .action(ArgAction::SetTrue)
+            .help("Use the nearest parent directory containing .git, Cargo.toml or package.json"),
+    ]
+}
 
-    if !current_dir.is_dir() {
-        return Err(anyhow::anyhow!("Current path is not a directory"));
-    }
+/// Output options accepted by every command that produces an export.
+fn output_args() -> Vec<Arg> {
+    vec![
+        Arg::new("output")
+            .short('o')
+            .long("output")
+            .value_name("FILE")
+            .help("Output file name (default: codebase.txt)"),
+        Arg::new("clipboard")
+            .long("clipboard")
+            .action(ArgAction::SetTrue)
+            .conflicts_with_all(["output", "max_part_size"])
+            .help("Copy the export to the system clipboard instead of writing a file"),
+        Arg::new("watch")
+            .short('w')
+            .long("watch")
+            .action(ArgAction::SetTrue)
+            .help("Keep running and regenerate the export whenever a selected file changes"),
+        Arg::new("watch_dirs")
+            .long("watch-dirs")
+            .action(ArgAction::SetTrue)
+            .requires("watch")
+            .help("With --watch, also watch the searched directories for new and deleted files, so --grep picks up new matches"),
+        Arg::new("line_numbers")
+            .short('l')
+            .long("line-numbers")
+            .action(ArgAction::SetTrue)
+            .help("Include line numbers in file contents"),
+        Arg::new("line_number_format")
+            .long("line-number-format")
+            .value_name("FORMAT")
+            .help("Gutter for numbered lines, with {n} for the line number (default: \"{n} | \")"),
+        Arg::new("no_tree")
+            .long("no-tree")
+            .action(ArgAction::SetTrue)
+            .help("Skip directory tree generation"),
+        Arg::new("no_content")
+            .long("no-content")
+            .action(ArgAction::SetTrue)
+            .help("Only show file list, not contents"),
+        Arg::new("metadata")
+            .short('m')
+            .long("metadata")
+            .action(ArgAction::SetTrue)
+            .help(
+                "Annotate files with size, line count, language, permissions and modification time",
+            ),
+        Arg::new("summary")
+            .long("summary")
+            .action(ArgAction::SetTrue)
+            .help("Add a per-language and per-extension summary table to the header"),
+        Arg::new("strip_comments")
+            .long("strip-comments")
+            .action(ArgAction::SetTrue)
+            .help("Strip comments from file contents (language-aware)"),
+        Arg::new("collapse_blank_lines")
+            .long("collapse-blank-lines")
+            .action(ArgAction::SetTrue)
+            .help("Collapse runs of blank lines into a single blank line"),
+        Arg::new("trim_whitespace")
+            .long("trim-whitespace")
+            .action(ArgAction::SetTrue)
+            .help("Trim trailing whitespace from every line"),
+        Arg::new("normalize_line_endings")
+            .long("normalize-line-endings")
+            .action(ArgAction::SetTrue)
+            .help("Convert CRLF and CR line endings to LF"),
+        Arg::new("tab_width")
+            .long("tab-width")
+            .value_name("N")
+            .value_parser(clap::value_parser!(usize))
+            .help("Expand tabs to spaces with tab stops every N columns"),
+        Arg::new("strip_bom")
+            .long("strip-bom")
+            .action(ArgAction::SetTrue)
+            .help("Remove a leading UTF-8 byte order mark from files"),
+        Arg::new("outline")
+            .long("outline")
+            .action(ArgAction::SetTrue)
+            .help("Export source files as outlines (signatures only, bodies elided)"),
+        Arg::new("max_part_size")
+            .long("max-part-size")
+            .value_name("SIZE")
+            .value_parser(clap::value_parser!(usize))
+            .help("Split the export into parts of at most SIZE (see --part-unit)"),
+        Arg::new("part_unit")
+            .long("part-unit")
+            .value_name("UNIT")
+            .value_parser(["bytes", "tokens"])
+            .help("Unit for --max-part-size: bytes (default) or tokens"),
+        Arg::new("sort")
+            .long("sort")
+            .value_name("ORDER")
+            .value_parser(SortOrder::NAMES)
+            .help("Order of file contents: path (default), config, dependencies, size, modified or entry-points"),
+        Arg::new("follow_deps")
+            .long("follow-deps")
+            .value_name("DEPTH")
+            .num_args(0..=1)
+            .default_missing_value("1")
+            .value_parser(clap::value_parser!(usize))
+            .help("Also export local files imported by the selection, up to DEPTH imports away (default 1)"),
+        Arg::new("redact")
+            .long("redact")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("no_redact")
+            .help("Redact secrets and credentials from file contents"),
+        Arg::new("no_redact")
+            .long("no-redact")
+            .action(ArgAction::SetTrue)
+            .help("Disable redaction of secrets, even if the configuration file enables it"),
+        Arg::new("on_error")
+            .long("on-error")
+            .value_name("POLICY")
+            .value_parser(["fail", "warn", "embed"])
+            .help("What to do with missing or unreadable files: fail, warn (skip, the default) or embed the error"),
+        Arg::new("strict")
+            .long("strict")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("on_error")
+            .help("Fail on missing or unreadable files (same as --on-error fail)"),
+    ]
+}
 
-    let mut state_manager = StateManager::new(&current_dir);
+/// Builds the workspace from the `PATH` and `--root` directories, applying
+/// `--detect-root` to each. Defaults to the current directory.
+fn workspace(matches: &ArgMatches) -> Result<Workspace> {
+    let explicit: Vec<&String> = matches
+        .try_get_many::<String>("path")
+        .ok()
+        .flatten()
+        .into_iter()
+        .flatten()
+        .chain(matches.get_many::<String>("root").into_iter().flatten())
+        .collect();
 
-    let selected_files = if let Some(config_file) = matches.get_one::<String>("config") {
-        // Validate config file exists and is readable
-        if !std::path::Path::new(config_file).exists() {
+    let mut roots = Vec::new();
+    if explicit.is_empty() {
+        roots.push(detect_root(matches, current_dir()?));
+    }
+    for path in explicit {
+        let root = Path::new(path);
+        if !root.exists() {
             return Err(
-                TreeTxtError::InvalidPath(format!("Config file not found: {config_file}")).into(),
+                TreeTxtError::InvalidPath(format!("Root directory not found: {path}")).into(),
             );
         }
+        if !root.is_dir() {
+            return Err(
+                TreeTxtError::InvalidPath(format!("Root path is not a directory: {path}")).into(),
+            );
+        }
+        roots.push(detect_root(matches, root.canonicalize()?));
+    }
 
-        let config = Config::from_file(config_file).map_err(|e| {
-            TreeTxtError::ConfigError(format!("Failed to parse config file '{config_file}': {e}"))
-        })?;
+    Ok(Workspace::new(roots))
+}
 
-        // Validate that files in config exist
-        let mut valid_files = Vec::new();
-        for file_path in config.files {
-            if file_path.exists() {
-                if file_path.is_file() {
-                    valid_files.push(file_path);
-                } else {
-                    eprintln!("Warning: Skipping '{}' - not a file", file_path.display());
-                }
-            } else {
-                eprintln!("Warning: File not found: '{}'", file_path.display());
+/// Replaces `start` with its project root if `--detect-root` was given.
+fn detect_root(matches: &ArgMatches, start: PathBuf) -> PathBuf {
+    if !matches.get_flag("detect_root") {
+        return start;
+    }
+
+    match find_project_root(&start) {
+        Some(root) => {
+            if root != start {
+                eprintln!("Using project root: {}", root.display());
             }
+            root
         }
-
-        if valid_files.is_empty() {
-            return Err(anyhow::anyhow!("No valid files found in config file"));
+        None => {
+            eprintln!(
+                "Warning: No project root found above '{}', using it as the root",
+                start.display()
+            );
+            start
         }
+    }
+}
 
-        valid_files
-    } else {
-        let mut file_selector = FileSelector::new(&current_dir);
+fn current_dir() -> Result<PathBuf> {
+    let current_dir = env::current_dir()
+        .context("Failed to get current directory. Please ensure you're in a valid directory.")?;
 
-        // Load previous selections if they exist
-        if let Ok(previous_selections) = state_manager.load_selections() {
-            file_selector.set_selections(previous_selections);
+    // Validate that current directory exists and is readable
+    if !current_dir.exists() {
+        return Err(TreeTxtError::InvalidPath("Current directory does not exist".into()).into());
+    }
+
+    if !current_dir.is_dir() {
+        return Err(TreeTxtError::InvalidPath("Current path is not a directory".into()).into());
+    }
+
+    Ok(current_dir)
+}
+
+/// Runs the interactive file selector, restoring and saving the project's selections.
+fn select_interactively(workspace: &Workspace) -> Result<(Vec<PathBuf>, OutputFormat)> {
+    let mut state_manager = StateManager::for_workspace(workspace);
+    let user_config = UserConfig::load()?;
+    let keymap = user_config.keymap()?;
+    let theme = user_config.theme()?;
+    let mut file_selector = FileSelector::with_workspace(workspace.clone());
+    file_selector.set_keymap(keymap);
+    file_selector.set_theme(theme);
+
+    // Load previous selections if they exist
+    if let Ok(previous_selections) = state_manager.load_selections() {
+        file_selector.set_selections(previous_selections);
+    }
+
+    let selections = file_selector
+        .run_interactive()
+        .context("File selection failed")?;
+
+    // Validate selections
+    if selections.is_empty() {
+        return Err(TreeTxtError::NoFilesSelected.into());
+    }
+
+    // Save selections for next time (ignore save errors - not critical)
+    if let Err(e) = state_manager.save_selections(&selections) {
+        eprintln!("Warning: Failed to save selections for next time: {e}");
+    }
+
+    let format = OutputFormat {
+        outline_mode: file_selector.outline_mode(),
+        outline_files: file_selector.outline_files(),
+        line_ranges: file_selector.line_ranges(),
+        ..Default::default()
+    };
+    Ok((selections, format))
+}
+
+fn load_config_selection(config_file: &str) -> Result<(Vec<PathBuf>, OutputFormat)> {
+    // Validate config file exists and is readable
+    if !Path::new(config_file).exists() {
+        return Err(
+            TreeTxtError::InvalidPath(format!("Config file not found: {config_file}")).into(),
+        );
+    }
+
+    let config = Config::from_file(config_file).map_err(|e| {
+        TreeTxtError::ConfigError(format!("Failed to parse config file '{config_file}': {e}"))
+    })?;
+
+    if config.files.is_empty() {
+        return Err(anyhow::Error::from(TreeTxtError::NoFilesSelected)
+            .context("The config file lists no files"));
+    }
+
+    // Missing entries are kept and handled by the export's error policy
+    let mut format = config.output_format;
+    format.line_ranges = absolute_line_ranges(format.line_ranges);
+    Ok((absolute_files(config.files), format))
+}
+
+/// Collects the files for `tree-txt export` from a config file, `--file`
+/// arguments, saved selections and `--grep` matches. `--grep` matches are
+/// listed on stderr if `report_matches` is set.
+fn select_non_interactively(
+    matches: &ArgMatches,
+    workspace: &Workspace,
+    report_matches: bool,
+) -> Result<(Vec<PathBuf>, OutputFormat)> {
+    let (mut files, mut format) = match matches.get_one::<String>("config") {
+        Some(config_file) => load_config_selection(config_file)?,
+        None => (Vec::new(), OutputFormat::default()),
+    };
+
+    if let Some(extra_files) = matches.get_many::<String>("file") {
+        let mut cli_files = Config {
+            files: extra_files.map(PathBuf::from).collect(),
+            output_format: OutputFormat::default(),
+        };
+        cli_files.split_line_ranges();
+        files.extend(absolute_files(cli_files.files));
+        for (path, ranges) in absolute_line_ranges(cli_files.output_format.line_ranges) {
+            format.line_ranges.entry(path).or_default().extend(ranges);
         }
+    }
 
-        let selections = file_selector
-            .run_interactive()
-            .map_err(|e| anyhow::anyhow!("File selection failed: {}", e))?;
+    if matches.get_flag("saved") {
+        let saved = StateManager::for_workspace(workspace)
+            .load_selections()
+            .context("Failed to load saved selections")?;
+        if saved.is_empty() {
+            eprintln!("Warning: No saved selections for this project");
+        }
+        files.extend(absolute_files(saved));
+    }
 
-        // Validate selections
-        if selections.is_empty() {
-            return Err(TreeTxtError::NoFilesSelected.into());
+    if let Some(pattern) = matches.get_one::<String>("grep") {
+        let query = Query::new(
+            pattern,
+            matches.get_flag("fixed_strings"),
+            matches.get_flag("ignore_case"),
+        )?;
+        let found = search::search(workspace, &query, false);
+        if report_matches {
+            report_search_matches(workspace, pattern, &found);
         }
 
-        // Save selections for next time (ignore save errors - not critical)
-        if let Err(e) = state_manager.save_selections(&selections) {
-            eprintln!("Warning: Failed to save selections for next time: {e}");
+        let context = matches.get_one::<usize>("context");
+        for file in found {
+            let path = absolute_files(vec![file.path.clone()]).remove(0);
+            if let Some(&context) = context {
+                format
+                    .line_ranges
+                    .entry(path.clone())
+                    .or_default()
+                    .extend(file.regions(context));
+            }
+            files.push(path);
         }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    files.retain(|file| seen.insert(file.clone()));
 
-        selections
+    if files.is_empty() {
+        return Err(TreeTxtError::NoFilesSelected.into());
+    }
+
+    Ok((files, format))
+}
+
+/// Lists the files found by `--grep` with their hit counts on stderr.
+fn report_search_matches(workspace: &Workspace, pattern: &str, found: &[FileMatch]) {
+    if found.is_empty() {
+        eprintln!("Warning: No files match '{pattern}'");
+        return;
+    }
+
+    let hits: usize = found.iter().map(|file| file.hits).sum();
+    eprintln!(
+        "Found {hits} match{} for '{pattern}' in {} file{}:",
+        if hits == 1 { "" } else { "es" },
+        found.len(),
+        if found.len() == 1 { "" } else { "s" }
+    );
+    for file in found {
+        eprintln!(
+            "   {} ({} hit{})",
+            workspace.display_path(&file.path).display(),
+            file.hits,
+            if file.hits == 1 { "" } else { "s" }
+        );
+    }
+}
+
+/// Makes `paths` absolute so they no longer depend on the working directory.
+///
+/// Existing paths are canonicalized. Missing paths are kept, so the export's
+/// error policy decides what happens to them.
+fn absolute_files(paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    paths
+        .into_iter()
+        .map(|path| {
+            path.canonicalize()
+                .or_else(|_| std::path::absolute(&path))
+                .unwrap_or(path)
+        })
+        .collect()
+}
+
+/// Makes the keys of `line_ranges` absolute, matching [`absolute_files`].
+fn absolute_line_ranges(
+    line_ranges: BTreeMap<PathBuf, Vec<LineRange>>,
+) -> BTreeMap<PathBuf, Vec<LineRange>> {
+    let mut absolute = BTreeMap::new();
+    for (path, ranges) in line_ranges {
+        let path = absolute_files(vec![path]).remove(0);
+        absolute.entry(path).or_insert_with(Vec::new).extend(ranges);
+    }
+    absolute
+}
+
+/// Resolves a selection again from its original sources, for watch mode.
+type Reselect<'a> = &'a dyn Fn() -> Result<(Vec<PathBuf>, OutputFormat)>;
+
+/// Applies the output options in `matches` to `base_format` and exports
+/// `selected_files`, then keeps watching them if `--watch` was given.
+///
+/// While watching, every change resolves the selection again with `reselect`,
+/// so edits to the config file and new `--grep` matches are picked up. An
+/// interactive selection has no `reselect` and stays as it was.
+fn export_selection(
+    matches: &ArgMatches,
+    workspace: &Workspace,
+    selected_files: &[PathBuf],
+    base_format: OutputFormat,
+    reselect: Option<Reselect>,
+) -> Result<()> {
+    let followed;
+    let selected_files = match matches.get_one::<usize>("follow_deps") {
+        Some(&depth) => {
+            followed = follow_dependencies(workspace, selected_files, depth);
+            &followed
+        }
+        None => selected_files,
     };
 
     let output_file = matches
@@ -165,41 +717,569 @@ fn main() -> Result<()> {
         .map(|s| s.as_str())
         .unwrap_or("codebase.txt");
 
+    let to_clipboard = matches.get_flag("clipboard");
+
     // Validate output file path
     let output_path = Path::new(output_file);
-    if let Some(parent) = output_path.parent() {
-        if !parent.as_os_str().is_empty() && !parent.exists() {
-            return Err(anyhow::anyhow!(
-                "Output directory does not exist: {}",
-                parent.display()
-            ));
+    if !to_clipboard {
+        if let Some(parent) = output_path.parent() {
+            if !parent.as_os_str().is_empty() && !parent.exists() {
+                return Err(TreeTxtError::InvalidPath(format!(
+                    "Output directory does not exist: {}",
+                    parent.display()
+                ))
+                .into());
+            }
+            if parent.exists() && !parent.is_dir() {
+                return Err(TreeTxtError::InvalidPath(format!(
+                    "Output parent path is not a directory: {}",
+                    parent.display()
+                ))
+                .into());
+            }
         }
-        if parent.exists() && !parent.is_dir() {
-            return Err(anyhow::anyhow!(
-                "Output parent path is not a directory: {}",
-                parent.display()
-            ));
+
+        // Check if output file already exists and warn user
+        if output_path.exists() {
+            eprintln!(
+                "Warning: Output file '{output_file}' already exists and will be overwritten"
+            );
         }
     }
 
-    // Check if output file already exists and warn user
-    if output_path.exists() {
-        eprintln!("Warning: Output file '{output_file}' already exists and will be overwritten");
-    }
+    let output_config = output_format(matches, base_format);
 
-    // Create output configuration based on CLI args
-    let output_config = config::OutputFormat {
-        include_line_numbers: matches.get_flag("line_numbers"),
-        include_tree: !matches.get_flag("no_tree"),
-        include_file_contents: !matches.get_flag("no_content"),
-        ..Default::default()
+    let mut output_generator = OutputGenerator::new();
+    if selected_files.len() >= PROGRESS_MIN_FILES && io::stderr().is_terminal() {
+        let drawn = Mutex::new(None);
+        output_generator =
+            output_generator.with_progress(move |progress| draw_progress(progress, &drawn));
+    }
+    let target = if to_clipboard {
+        ExportTarget::Clipboard
+    } else {
+        ExportTarget::File(output_file)
     };
 
-    let output_generator = OutputGenerator::new();
-    output_generator
-        .generate_with_config(&current_dir, &selected_files, output_file, &output_config)
-        .map_err(|e| anyhow::anyhow!("Failed to generate output file '{}': {}", output_file, e))?;
+    let exported = run_export(
+        &output_generator,
+        workspace,
+        selected_files,
+        &target,
+        &output_config,
+    )?;
+
+    match exported.clipboard {
+        Some(backend) => println!(
+            "📋 Copied export to clipboard via {backend} ({}, ~{} tokens)",
+            format_size(exported.size),
+            exported.tokens
+        ),
+        None if exported.files_written.len() > 1 => {
+            println!(
+                "✅ Successfully generated codebase export in {} parts:",
+                exported.files_written.len()
+            );
+            for path in &exported.files_written {
+                println!("   {}", path.display());
+            }
+        }
+        None => println!("✅ Successfully generated codebase text file: {output_file}"),
+    }
+
+    if matches.get_flag("watch") {
+        let absolute = |path: &PathBuf| std::path::absolute(path).unwrap_or_else(|_| path.clone());
+        let mut files: Vec<PathBuf> = selected_files.iter().map(absolute).collect();
+        let mut output_config = output_config;
+        let outputs: Vec<PathBuf> = exported.files_written.iter().map(absolute).collect();
+
+        // The config file is a source of the selection, so editing it re-resolves it
+        let sources: Vec<PathBuf> = match reselect {
+            Some(_) => matches
+                .get_one::<String>("config")
+                .map(|config_file| absolute(&PathBuf::from(config_file)))
+                .into_iter()
+                .collect(),
+            None => Vec::new(),
+        };
+        let watch_dirs = matches.get_flag("watch_dirs") && reselect.is_some();
+        if matches.get_flag("watch_dirs") && !watch_dirs {
+            eprintln!(
+                "Warning: --watch-dirs has no effect on an interactive selection, which is not re-resolved"
+            );
+        }
+        let search_dirs = || {
+            if watch_dirs {
+                search::directories(workspace, false)
+            } else {
+                Vec::new()
+            }
+        };
+        let start_watching = |files: &[PathBuf], dirs: &[PathBuf]| {
+            Watcher::new(&[files, &sources].concat())
+                .watch_dirs(dirs.to_vec())
+                .ignore(outputs.clone())
+        };
+
+        let mut dirs = search_dirs();
+        let mut watcher = start_watching(&files, &dirs);
+        let mut previous_size = exported.size;
+
+        println!(
+            "👀 Watching {} file{} for changes (Ctrl+C to stop)",
+            files.len(),
+            if files.len() == 1 { "" } else { "s" }
+        );
+
+        loop {
+            let changes = watcher.wait_for_changes();
+            let time = chrono::Local::now().format("%H:%M:%S");
+            let display = |path: &Path| workspace.display_path(path);
+            let mut summary = changes.summary_with(display);
+
+            if let Some(reselect) = reselect {
+                let (mut selection, format) = match reselect() {
+                    Ok(resolved) => resolved,
+                    Err(e) => {
+                        eprintln!("❌ [{time}] {summary} → selection failed: {e:#}");
+                        continue;
+                    }
+                };
+                if let Some(&depth) = matches.get_one::<usize>("follow_deps") {
+                    let imported = dependencies::follow(workspace, &selection, depth);
+                    selection.extend(imported.into_iter().map(|file| file.path));
+                }
+                let mut selection: Vec<PathBuf> = selection.iter().map(absolute).collect();
+                selection.retain(|file| !outputs.contains(file));
+
+                let selection_changes = Changes {
+                    added: difference(&selection, &files),
+                    removed: difference(&files, &selection),
+                    ..Changes::default()
+                };
+                if !selection_changes.is_empty() {
+                    summary = format!(
+                        "{summary}; selection: {}",
+                        selection_changes.summary_with(display)
+                    );
+                }
+
+                // Walking a large workspace is slow, so only look for new
+                // directories when one was added or removed
+                let dirs_changed = watch_dirs
+                    && (changes.added.iter().any(|path| path.is_dir())
+                        || changes.removed.iter().any(|path| dirs.contains(path)));
+                if dirs_changed {
+                    dirs = search_dirs();
+                }
+                if dirs_changed || !selection_changes.is_empty() {
+                    watcher = start_watching(&selection, &dirs);
+                }
+
+                files = selection;
+                output_config = output_format(matches, format);
+            }
+
+            match run_export(
+                &output_generator,
+                workspace,
+                &files,
+                &target,
+                &output_config,
+            ) {
+                Ok(exported) => {
+                    println!(
+                        "🔄 [{time}] {summary} → {} ({}, {})",
+                        target,
+                        format_size(exported.size),
+                        format_size_delta(previous_size, exported.size)
+                    );
+                    previous_size = exported.size;
+                }
+                Err(e) => eprintln!("❌ [{time}] {summary} → export failed: {e}"),
+            }
+        }
+    }
 
-    println!("✅ Successfully generated codebase text file: {output_file}");
     Ok(())
 }
+
+/// Returns the paths in `paths` that are not in `other`, in order.
+fn difference(paths: &[PathBuf], other: &[PathBuf]) -> Vec<PathBuf> {
+    paths
+        .iter()
+        .filter(|path| !other.contains(path))
+        .cloned()
+        .collect()
+}
+
+/// Applies the output options in `matches` on top of `base_format`, the
+/// output format of the config file.
+fn output_format(matches: &ArgMatches, base_format: OutputFormat) -> OutputFormat {
+    let mut output_config = base_format;
+    if matches.get_flag("line_numbers") {
+        output_config.include_line_numbers = true;
+    }
+    if let Some(format) = matches.get_one::<String>("line_number_format") {
+        output_config.line_number_format = format.clone();
+    }
+    if matches.get_flag("no_tree") {
+        output_config.include_tree = false;
+    }
+    if matches.get_flag("no_content") {
+        output_config.include_file_contents = false;
+    }
+    if matches.get_flag("metadata") {
+        output_config.show_file_size = true;
+        output_config.show_line_count = true;
+        output_config.show_modified_time = true;
+        output_config.show_language = true;
+        output_config.show_permissions = true;
+    }
+    if matches.get_flag("summary") {
+        output_config.include_summary = true;
+    }
+    if matches.get_flag("strip_comments") {
+        output_config.strip_comments = true;
+    }
+    if matches.get_flag("collapse_blank_lines") {
+        output_config.collapse_blank_lines = true;
+    }
+    if matches.get_flag("trim_whitespace") {
+        output_config.trim_trailing_whitespace = true;
+    }
+    if matches.get_flag("normalize_line_endings") {
+        output_config.normalize_line_endings = true;
+    }
+    if let Some(&tab_width) = matches.get_one::<usize>("tab_width") {
+        output_config.tab_width = Some(tab_width);
+    }
+    if matches.get_flag("strip_bom") {
+        output_config.strip_bom = true;
+    }
+    if matches.get_flag("outline") {
+        output_config.outline_mode = true;
+    }
+    if matches.get_flag("redact") {
+        output_config.redact_secrets = true;
+    }
+    if matches.get_flag("no_redact") {
+        output_config.redact_secrets = false;
+    }
+    if let Some(&max_part_size) = matches.get_one::<usize>("max_part_size") {
+        output_config.max_part_size = Some(max_part_size);
+    }
+    if let Some(order) = matches
+        .get_one::<String>("sort")
+        .and_then(|name| SortOrder::from_name(name))
+    {
+        output_config.sort_order = order;
+    }
+    if matches.get_flag("strict") {
+        output_config.on_error = ErrorPolicy::Fail;
+    } else if let Some(policy) = matches.get_one::<String>("on_error") {
+        output_config.on_error = match policy.as_str() {
+            "fail" => ErrorPolicy::Fail,
+            "embed" => ErrorPolicy::Embed,
+            _ => ErrorPolicy::Warn,
+        };
+    }
+    if let Some(unit) = matches.get_one::<String>("part_unit") {
+        output_config.part_size_unit = match unit.as_str() {
+            "tokens" => SizeUnit::Tokens,
+            _ => SizeUnit::Bytes,
+        };
+    }
+    output_config
+}
+
+/// Adds the local files imported by `selected_files`, up to `depth` imports
+/// away, and reports each addition on stderr.
+fn follow_dependencies(
+    workspace: &Workspace,
+    selected_files: &[PathBuf],
+    depth: usize,
+) -> Vec<PathBuf> {
+    let added = dependencies::follow(workspace, selected_files, depth);
+    if added.is_empty() {
+        eprintln!("No imported files to add");
+        return selected_files.to_vec();
+    }
+
+    eprintln!(
+        "Added {} imported file{}:",
+        added.len(),
+        if added.len() == 1 { "" } else { "s" }
+    );
+    for file in &added {
+        eprintln!(
+            "   {} (imported by {})",
+            workspace.display_path(&file.path).display(),
+            workspace.display_path(&file.imported_by).display()
+        );
+    }
+
+    let sensitive: Vec<String> = added
+        .iter()
+        .filter(|file| is_sensitive_filename(&file.path))
+        .map(|file| workspace.display_path(&file.path).display().to_string())
+        .collect();
+    if !sensitive.is_empty() {
+        eprintln!(
+            "Warning: Imported file(s) may contain secrets: {}",
+            sensitive.join(", ")
+        );
+    }
+
+    let mut files = selected_files.to_vec();
+    files.extend(added.into_iter().map(|file| file.path));
+    files
+}
+
+/// Handles `tree-txt state show|clear|list`.
+fn run_state(matches: &ArgMatches) -> Result<()> {
+    let workspace = workspace(matches)?;
+    let mut state_manager = StateManager::for_workspace(&workspace);
+
+    match matches.subcommand() {
+        Some(("show", _)) => match state_manager.load_project_state()? {
+            Some(project_state) => {
+                println!("Project: {}", state_manager.project_key());
+                println!(
+                    "Last updated: {}",
+                    format_timestamp(project_state.last_updated)
+                );
+                println!("Selected files ({}):", project_state.selected_files.len());
+                let mut files = project_state.selected_files;
+                files.sort();
+                for file in files {
+                    println!("  {}", workspace.display_path(&file).display());
+                }
+            }
+            None => println!("No saved selections for {}", state_manager.project_key()),
+        },
+        Some(("clear", _)) => {
+            if state_manager.clear_selections()? {
+                println!(
+                    "🗑  Cleared saved selections for {}",
+                    state_manager.project_key()
+                );
+            } else {
+                println!("No saved selections for {}", state_manager.project_key());
+            }
+        }
+        Some(("list", _)) => {
+            let projects = state_manager.list_projects()?;
+            if projects.is_empty() {
+                println!(
+                    "No saved selections in {}",
+                    state_manager.state_file().display()
+                );
+            }
+            for (key, project_state) in projects {
+                let marker = if key == state_manager.project_key() {
+                    "*"
+                } else {
+                    " "
+                };
+                println!(
+                    "{marker} {key}  ({} files, updated {})",
+                    project_state.selected_files.len(),
+                    format_timestamp(project_state.last_updated)
+                );
+            }
+        }
+        _ => unreachable!("state requires a subcommand"),
+    }
+
+    Ok(())
+}
+
+/// Handles `tree-txt init`, writing a starter configuration file.
+fn run_init(matches: &ArgMatches) -> Result<()> {
+    let path = Path::new(
+        matches
+            .get_one::<String>("path")
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_CONFIG_FILE),
+    );
+
+    if path.exists() && !matches.get_flag("force") {
+        return Err(TreeTxtError::InvalidPath(format!(
+            "'{}' already exists (use --force to overwrite)",
+            path.display()
+        ))
+        .into());
+    }
+
+    let files = if matches.get_flag("from_saved") {
+        let mut saved = StateManager::new(&current_dir()?).load_selections()?;
+        saved.sort();
+
+        // Entries in a config file are relative to the file's own directory
+        let config_dir = std::path::absolute(path)?
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        saved
+            .into_iter()
+            .map(|file| {
+                file.strip_prefix(&config_dir)
+                    .map(Path::to_path_buf)
+                    .unwrap_or(file)
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    std::fs::write(path, Config::scaffold(&files))?;
+    println!(
+        "✅ Created {} with {} files. Export it with: tree-txt export -c {}",
+        path.display(),
+        files.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Formats a Unix timestamp from the state file in local time.
+fn format_timestamp(seconds: u64) -> String {
+    chrono::DateTime::from_timestamp(seconds as i64, 0)
+        .map(|time| {
+            time.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Where an export is delivered.
+enum ExportTarget<'a> {
+    File(&'a str),
+    Clipboard,
+}
+
+impl fmt::Display for ExportTarget<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "regenerated {path}"),
+            Self::Clipboard => write!(f, "copied to clipboard"),
+        }
+    }
+}
+
+/// Outcome of a single export run.
+struct Exported {
+    files_written: Vec<PathBuf>,
+    clipboard: Option<Backend>,
+    size: u64,
+    tokens: usize,
+}
+
+/// Draws `progress` as a bar on stderr, skipping updates that would not
+/// change it. `drawn` holds the stage and percentage currently shown.
+fn draw_progress(progress: Progress, drawn: &Mutex<Option<(&'static str, usize)>>) {
+    let Ok(mut drawn) = drawn.lock() else {
+        return;
+    };
+    let (stage, done, total) = match progress {
+        Progress::Reading { done, total } => ("Reading", done, total),
+        Progress::Rendering { done, total } => ("Rendering", done, total),
+        Progress::Finished => {
+            if drawn.take().is_some() {
+                eprint!("\r\x1b[2K");
+            }
+            return;
+        }
+    };
+
+    // Files finish out of order, so never move the bar backwards
+    let percent = done * 100 / total.max(1);
+    if drawn.is_some_and(|(shown, shown_percent)| shown == stage && shown_percent >= percent) {
+        return;
+    }
+    *drawn = Some((stage, percent));
+
+    let filled = percent * PROGRESS_BAR_WIDTH / 100;
+    eprint!(
+        "\r{stage:<9} [{}{}] {percent:>3}% ({done}/{total})",
+        "#".repeat(filled),
+        "-".repeat(PROGRESS_BAR_WIDTH - filled)
+    );
+    let _ = io::stderr().flush();
+}
+
+/// Renders the export and delivers it to `target`, printing any redactions and
+/// problems with selected files to stderr.
+fn run_export(
+    output_generator: &OutputGenerator,
+    workspace: &Workspace,
+    selected_files: &[PathBuf],
+    target: &ExportTarget,
+    output_config: &OutputFormat,
+) -> Result<Exported> {
+    match target {
+        ExportTarget::Clipboard => {
+            let (text, report) = output_generator
+                .render_workspace(workspace, selected_files, output_config)
+                .context("Failed to generate export")?;
+
+            if let Some(redaction_report) = report.redaction_report() {
+                eprint!("{redaction_report}");
+            }
+            if let Some(problem_report) = report.problem_report() {
+                eprint!("{problem_report}");
+            }
+
+            let backend = clipboard::copy(&text)?;
+            Ok(Exported {
+                files_written: Vec::new(),
+                clipboard: Some(backend),
+                size: text.len() as u64,
+                tokens: estimate_tokens(&text),
+            })
+        }
+        ExportTarget::File(output_file) => {
+            let report = output_generator
+                .generate_workspace(workspace, selected_files, output_file, output_config)
+                .with_context(|| format!("Failed to generate output file '{output_file}'"))?;
+
+            if let Some(redaction_report) = report.redaction_report() {
+                eprint!("{redaction_report}");
+            }
+            if let Some(problem_report) = report.problem_report() {
+                eprint!("{problem_report}");
+            }
+
+            for path in &report.stale_parts_removed {
+                eprintln!(
+                    "🗑  Removed leftover part from an earlier export: {}",
+                    path.display()
+                );
+            }
+
+            let size = report
+                .files_written
+                .iter()
+                .filter_map(|path| std::fs::metadata(path).ok())
+                .map(|metadata| metadata.len())
+                .sum();
+            Ok(Exported {
+                files_written: report.files_written,
+                clipboard: None,
+                size,
+                tokens: 0,
+            })
+        }
+    }
+}
+
+/// Formats the change in export size, e.g. `+212 B` or `-1.5 KiB`.
+fn format_size_delta(before: u64, after: u64) -> String {
+    if after >= before {
+        format!("+{}", format_size(after - before))
+    } else {
+        format!("-{}", format_size(before - after))
+    }
+}
@@ -0,0 +1,161 @@
+//! # File Metadata
+//!
+//! Collects per-file metadata (size, line count, modification time, language
+//! and permissions) used to annotate exports.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::path::Path;
+//! use tree_txt::metadata::{FileMetadata, format_size};
+//!
+//! assert_eq!(format_size(512), "512 B");
+//! assert_eq!(format_size(2048), "2.0 KiB");
+//!
+//! let metadata = FileMetadata::from_path(Path::new("Cargo.toml"), Some("[package]\nname = \"x\"\n"))?;
+//! assert_eq!(metadata.lines, 2);
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::config::OutputFormat;
+use crate::language::Language;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Metadata gathered for a single exported file.
+#[derive(Debug, Clone)]
+pub struct FileMetadata {
+    /// File size in bytes
+    pub size: u64,
+    /// Number of lines in the file, or 0 if its contents were not read
+    pub lines: usize,
+    /// Last modification time, if the platform reports one
+    pub modified: Option<SystemTime>,
+    /// Language detected from the file name
+    pub language: Language,
+    /// Permission string (`rwxr-xr-x` on Unix, `read-only`/`read-write` elsewhere)
+    pub permissions: String,
+    /// Whether the file has an executable bit set
+    pub executable: bool,
+}
+
+impl FileMetadata {
+    /// Reads metadata for the file at `path`. Lines are counted in `text`,
+    /// the file's contents if the caller has already read them, so the file
+    /// is not read a second time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be stat'ed.
+    pub fn from_path(path: &Path, text: Option<&str>) -> io::Result<Self> {
+        let metadata = fs::metadata(path)?;
+
+        let (permissions, executable) = describe_permissions(&metadata);
+
+        Ok(Self {
+            size: metadata.len(),
+            lines: text.map_or(0, count_lines),
+            modified: metadata.modified().ok(),
+            language: Language::detect(path),
+            permissions,
+            executable,
+        })
+    }
+
+    /// Returns the annotations enabled in `config`, in display order.
+    pub fn annotations(&self, config: &OutputFormat) -> Vec<String> {
+        let mut parts = Vec::new();
+
+        if config.show_file_size {
+            parts.push(format_size(self.size));
+        }
+        if config.show_line_count {
+            let noun = if self.lines == 1 { "line" } else { "lines" };
+            parts.push(format!("{} {noun}", self.lines));
+        }
+        if config.show_language && self.language != Language::Unknown {
+            parts.push(self.language.name().to_string());
+        }
+        if config.show_permissions {
+            if self.executable {
+                parts.push(format!("{} (executable)", self.permissions));
+            } else {
+                parts.push(self.permissions.clone());
+            }
+        }
+        if config.show_modified_time
+            && let Some(modified) = self.modified
+        {
+            let modified: chrono::DateTime<chrono::Utc> = modified.into();
+            parts.push(format!(
+                "modified {}",
+                modified.format("%Y-%m-%d %H:%M UTC")
+            ));
+        }
+
+        parts
+    }
+}
+
+/// Formats a byte count using binary units (B, KiB, MiB, GiB).
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+fn count_lines(text: &str) -> usize {
+    if text.is_empty() {
+        return 0;
+    }
+
+    let newlines = text.matches('\n').count();
+    if text.ends_with('\n') {
+        newlines
+    } else {
+        newlines + 1
+    }
+}
+
+#[cfg(unix)]
+fn describe_permissions(metadata: &fs::Metadata) -> (String, bool) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = metadata.permissions().mode();
+    let flags = ['r', 'w', 'x'];
+    let description = (0..9)
+        .map(|bit| {
+            if mode & (0o400 >> bit) != 0 {
+                flags[bit % 3]
+            } else {
+                '-'
+            }
+        })
+        .collect();
+
+    (description, mode & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn describe_permissions(metadata: &fs::Metadata) -> (String, bool) {
+    let description = if metadata.permissions().readonly() {
+        "read-only"
+    } else {
+        "read-write"
+    };
+
+    (description.to_string(), false)
+}
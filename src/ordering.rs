@@ -0,0 +1,153 @@
+//! # File Ordering
+//!
+//! Decides the order in which files appear in the contents section of an
+//! export. The directory tree is always hierarchical; only the file contents
+//! follow the chosen [`SortOrder`].
+//!
+//! # Examples
+//!
+//! ```rust
+//! use tree_txt::ordering::{SortOrder, sort_files};
+//! use tree_txt::project::Workspace;
+//! use std::path::{Path, PathBuf};
+//!
+//! let workspace = Workspace::single(Path::new("/project"));
+//! let files = vec![
+//!     PathBuf::from("/project/src/util.rs"),
+//!     PathBuf::from("/project/README.md"),
+//!     PathBuf::from("/project/src/main.rs"),
+//! ];
+//!
+//! let sorted = sort_files(&workspace, &files, SortOrder::EntryPoints, |_| None::<String>);
+//! assert_eq!(
+//!     sorted,
+//!     vec![
+//!         PathBuf::from("/project/README.md"),
+//!         PathBuf::from("/project/src/main.rs"),
+//!         PathBuf::from("/project/src/util.rs"),
+//!     ]
+//! );
+//! ```
+
+use crate::dependencies;
+use crate::project::Workspace;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Order of the files in the contents section of an export.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortOrder {
+    /// By path, grouped by root in multi-root workspaces
+    #[default]
+    Path,
+    /// In the order the files were listed (config file, then `--file`)
+    Config,
+    /// Imported files before the files that import them
+    Dependencies,
+    /// Smallest files first
+    Size,
+    /// Least recently modified files first
+    Modified,
+    /// READMEs, manifests and entry points such as `main.rs` first, then by path
+    EntryPoints,
+}
+
+impl SortOrder {
+    /// Names accepted on the command line and in configuration files.
+    pub const NAMES: [&'static str; 6] = [
+        "path",
+        "config",
+        "dependencies",
+        "size",
+        "modified",
+        "entry-points",
+    ];
+
+    /// Parses a name from [`NAMES`](Self::NAMES).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "path" => Some(Self::Path),
+            "config" => Some(Self::Config),
+            "dependencies" => Some(Self::Dependencies),
+            "size" => Some(Self::Size),
+            "modified" => Some(Self::Modified),
+            "entry-points" => Some(Self::EntryPoints),
+            _ => None,
+        }
+    }
+}
+
+/// File names that typically explain or start a project, most important first.
+const ENTRY_POINTS: &[&str] = &[
+    "readme",
+    "cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "go.mod",
+    "main.rs",
+    "lib.rs",
+    "main.py",
+    "__main__.py",
+    "main.go",
+    "index.ts",
+    "index.js",
+    "app.py",
+];
+
+/// Returns `files` in `order`.
+///
+/// `source` returns the contents of a file and is only used for
+/// [`SortOrder::Dependencies`]. Ties are broken by path, so the result is
+/// deterministic.
+pub fn sort_files<S: AsRef<str>>(
+    workspace: &Workspace,
+    files: &[PathBuf],
+    order: SortOrder,
+    source: impl Fn(&Path) -> Option<S>,
+) -> Vec<PathBuf> {
+    let mut by_path = files.to_vec();
+    by_path.sort_by(|a, b| (workspace.root_index(a), a).cmp(&(workspace.root_index(b), b)));
+
+    match order {
+        SortOrder::Path => by_path,
+        SortOrder::Config => files.to_vec(),
+        SortOrder::Dependencies => dependencies::dependency_order(&by_path, source),
+        SortOrder::Size => {
+            by_path.sort_by_cached_key(|path| fs::metadata(path).map_or(0, |m| m.len()));
+            by_path
+        }
+        SortOrder::Modified => {
+            by_path.sort_by_cached_key(|path| {
+                fs::metadata(path)
+                    .and_then(|m| m.modified())
+                    .unwrap_or(SystemTime::UNIX_EPOCH)
+            });
+            by_path
+        }
+        SortOrder::EntryPoints => {
+            by_path.sort_by_cached_key(|path| entry_point_rank(workspace, path));
+            by_path
+        }
+    }
+}
+
+/// Ranks entry points by importance and, among equals, by depth, so the
+/// top-level README comes before one in a subdirectory. Other files rank last.
+fn entry_point_rank(workspace: &Workspace, path: &Path) -> (usize, usize) {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let stem = name.split('.').next().unwrap_or_default();
+
+    let rank = ENTRY_POINTS
+        .iter()
+        .position(|entry| *entry == name || (*entry == "readme" && stem == "readme"));
+    match rank {
+        Some(rank) => (rank, workspace.display_path(path).components().count()),
+        None => (ENTRY_POINTS.len(), 0),
+    }
+}
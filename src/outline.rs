@@ -0,0 +1,190 @@
+//! # Outline Export
+//!
+//! Reduces source files to their outline - module docs, type definitions,
+//! function signatures and impl headers - with function bodies elided as
+//! `{ … }`. Useful for showing the shape of a large codebase without every
+//! implementation detail.
+//!
+//! Languages are supported through the [`OutlineParser`] trait; use
+//! [`parser_for`] to look up the parser for a language. Rust is currently
+//! supported.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use tree_txt::language::Language;
+//! use tree_txt::outline::parser_for;
+//!
+//! let source = "/// Adds numbers.\npub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n";
+//! let parser = parser_for(Language::Rust).unwrap();
+//!
+//! assert_eq!(
+//!     parser.outline(source),
+//!     "/// Adds numbers.\npub fn add(a: i32, b: i32) -> i32 { … }\n"
+//! );
+//! ```
+
+use crate::language::Language;
+use crate::transform::literal_or_comment_len;
+
+/// Marker that replaces elided function bodies.
+pub const ELIDED_BODY: &str = "{ … }";
+
+/// Produces the outline of a source file in a particular language.
+pub trait OutlineParser: Send + Sync {
+    /// Returns `source` reduced to its outline.
+    fn outline(&self, source: &str) -> String;
+}
+
+/// Returns the outline parser for `language`, or `None` if outlines are not
+/// supported for it yet.
+pub fn parser_for(language: Language) -> Option<&'static dyn OutlineParser> {
+    match language {
+        Language::Rust => Some(&RustOutline),
+        _ => None,
+    }
+}
+
+/// Outline parser for Rust source files.
+///
+/// Keeps everything outside function bodies verbatim (docs, attributes,
+/// `use` items, type definitions, trait and impl blocks) and replaces the
+/// body of every `fn` with [`ELIDED_BODY`].
+pub struct RustOutline;
+
+impl OutlineParser for RustOutline {
+    fn outline(&self, source: &str) -> String {
+        let syntax = Language::Rust
+            .comment_syntax()
+            .expect("Rust has a comment syntax");
+
+        let mut outline = String::with_capacity(source.len() / 2);
+        // Start of the item header currently being scanned
+        let mut header_start = 0;
+        // Start of the source not yet copied to the outline
+        let mut copied = 0;
+        let mut pos = 0;
+
+        while pos < source.len() {
+            if let Some(len) = literal_or_comment_len(source, pos, &syntax) {
+                pos += len;
+                continue;
+            }
+
+            let ch = source[pos..].chars().next().unwrap_or_default();
+            match ch {
+                '{' if is_fn_header(&source[header_start..pos]) => {
+                    let end = matching_brace(source, pos);
+                    outline.push_str(&source[copied..pos]);
+                    outline.push_str(ELIDED_BODY);
+                    copied = end;
+                    pos = end;
+                    header_start = end;
+                    continue;
+                }
+                '{' | '}' | ';' => header_start = pos + 1,
+                _ => {}
+            }
+            pos += ch.len_utf8();
+        }
+
+        outline.push_str(&source[copied..]);
+        outline
+    }
+}
+
+/// Returns `true` if the item header (the code between the previous item
+/// boundary and an opening brace) introduces a function body.
+fn is_fn_header(header: &str) -> bool {
+    let syntax = Language::Rust
+        .comment_syntax()
+        .expect("Rust has a comment syntax");
+
+    let mut pos = 0;
+    let mut code = String::with_capacity(header.len());
+    while pos < header.len() {
+        if let Some(len) = literal_or_comment_len(header, pos, &syntax) {
+            code.push(' ');
+            pos += len;
+            continue;
+        }
+        let ch = header[pos..].chars().next().unwrap_or_default();
+        code.push(ch);
+        pos += ch.len_utf8();
+    }
+
+    // Skip attributes such as `#[inline]` so their contents are not mistaken for code.
+    // The first item keyword decides: `impl From<fn()> for X` is not a function.
+    strip_attributes(&code)
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .find(|word| ITEM_KEYWORDS.contains(word))
+        .is_some_and(|keyword| keyword == "fn")
+}
+
+const ITEM_KEYWORDS: [&str; 8] = [
+    "fn",
+    "impl",
+    "trait",
+    "mod",
+    "struct",
+    "enum",
+    "union",
+    "macro_rules",
+];
+
+fn strip_attributes(code: &str) -> String {
+    let mut result = String::with_capacity(code.len());
+    let mut depth = 0usize;
+    let mut chars = code.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if depth > 0 {
+            match ch {
+                '[' => depth += 1,
+                ']' => depth -= 1,
+                _ => {}
+            }
+            continue;
+        }
+        if ch == '#' {
+            chars.next_if_eq(&'!');
+            if chars.next_if_eq(&'[').is_some() {
+                depth = 1;
+                continue;
+            }
+        }
+        result.push(ch);
+    }
+
+    result
+}
+
+/// Returns the position just past the brace matching the one at `open`.
+fn matching_brace(source: &str, open: usize) -> usize {
+    let syntax = Language::Rust
+        .comment_syntax()
+        .expect("Rust has a comment syntax");
+
+    let mut depth = 0usize;
+    let mut pos = open;
+    while pos < source.len() {
+        if let Some(len) = literal_or_comment_len(source, pos, &syntax) {
+            pos += len;
+            continue;
+        }
+        let ch = source[pos..].chars().next().unwrap_or_default();
+        match ch {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return pos + 1;
+                }
+            }
+            _ => {}
+        }
+        pos += ch.len_utf8();
+    }
+
+    source.len()
+}
@@ -23,27 +23,191 @@
 //! };
 //!
 //! let files = vec![Path::new("src/main.rs").to_path_buf()];
+//! let output = std::env::temp_dir().join("tree-txt-doc-export.txt");
 //! generator.generate_with_config(
 //!     Path::new("."),
 //!     &files,
-//!     "export.txt",
+//!     output.to_str().unwrap(),
 //!     &config,
 //! )?;
-//! # Ok::<(), anyhow::Error>(())
+//! # Ok::<(), tree_txt::TreeTxtError>(())
 //! ```
 
+use crate::chunker::{self, Section};
 use crate::config::OutputFormat;
-use anyhow::Result;
-use std::collections::BTreeSet;
+use crate::encoding::{self, Decoded, EncodingOverrides};
+use crate::error::{ErrorPolicy, Result, TreeTxtError};
+use crate::language::Language;
+use crate::line_range::{self, LineRange};
+use crate::metadata::{FileMetadata, format_size};
+use crate::ordering;
+use crate::outline;
+use crate::project::Workspace;
+use crate::redaction::Redactor;
+use crate::transform;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// Generator for formatted text exports from file collections.
 ///
 /// Creates structured output with headers, directory trees, and file contents
 /// according to the specified configuration.
 pub struct OutputGenerator {
-    // Configuration options can be added here later
+    progress: Option<Box<dyn Fn(Progress) + Send + Sync>>,
+}
+
+/// Progress of an export, reported to the callback set with
+/// [`OutputGenerator::with_progress`].
+///
+/// Files are read and rendered on a thread pool, so the callback may be called
+/// from several threads at once and `done` values can arrive out of order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    /// `done` of `total` selected files have been read
+    Reading { done: usize, total: usize },
+    /// `done` of `total` files have been rendered
+    Rendering { done: usize, total: usize },
+    /// The export finished, successfully or not
+    Finished,
+}
+
+/// Reports [`Progress::Finished`] when an export ends, including on errors.
+struct FinishGuard<'a>(&'a OutputGenerator);
+
+impl Drop for FinishGuard<'_> {
+    fn drop(&mut self) {
+        self.0.report_progress(Progress::Finished);
+    }
+}
+
+/// Summary of a completed export.
+#[derive(Debug, Clone, Default)]
+pub struct ExportReport {
+    /// Secrets that were redacted from the export
+    pub redactions: Vec<RedactionEntry>,
+    /// Files written by the export (several when the export is split into parts)
+    pub files_written: Vec<PathBuf>,
+    /// Part files left over from an earlier export with more parts, which
+    /// were deleted
+    pub stale_parts_removed: Vec<PathBuf>,
+    /// Selected files that could not be exported as-is
+    pub problems: Vec<Problem>,
+}
+
+/// A selected file that was missing or could not be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    /// File the problem occurred with, as shown in the export
+    pub path: PathBuf,
+    /// Category of the problem
+    pub kind: ProblemKind,
+    /// Underlying error message
+    pub message: String,
+}
+
+/// Category of a [`Problem`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProblemKind {
+    /// The file does not exist
+    Missing,
+    /// The path is a directory or other non-file
+    NotAFile,
+    /// The file cannot be read due to its permissions
+    PermissionDenied,
+    /// The file contents cannot be decoded as text
+    Decode,
+    /// Any other read failure
+    Unreadable,
+}
+
+impl ProblemKind {
+    fn from_io(error: &std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::NotFound => Self::Missing,
+            std::io::ErrorKind::IsADirectory => Self::NotAFile,
+            std::io::ErrorKind::PermissionDenied => Self::PermissionDenied,
+            std::io::ErrorKind::InvalidData => Self::Decode,
+            _ => Self::Unreadable,
+        }
+    }
+
+    /// Short description, e.g. `not found`.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Missing => "not found",
+            Self::NotAFile => "not a file",
+            Self::PermissionDenied => "permission denied",
+            Self::Decode => "cannot be decoded",
+            Self::Unreadable => "unreadable",
+        }
+    }
+}
+
+/// Contents of each exported file, or the error message to embed instead.
+type FileContents = HashMap<PathBuf, std::result::Result<Decoded, String>>;
+
+/// A secret redacted from one of the exported files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedactionEntry {
+    /// File the secret was found in, relative to the base directory
+    pub path: PathBuf,
+    /// 1-based line number of the secret
+    pub line: usize,
+    /// Detector that matched, e.g. `aws-access-key`
+    pub kind: String,
+}
+
+impl ExportReport {
+    /// Formats the problems encountered during the export for display, or
+    /// returns `None` if there were none.
+    pub fn problem_report(&self) -> Option<String> {
+        if self.problems.is_empty() {
+            return None;
+        }
+
+        let noun = if self.problems.len() == 1 {
+            "problem"
+        } else {
+            "problems"
+        };
+        let mut report = format!("⚠ {} {noun} with selected files:\n", self.problems.len());
+        for problem in &self.problems {
+            report.push_str(&format!(
+                "  {}: {} ({})\n",
+                problem.path.display(),
+                problem.kind.description(),
+                problem.message
+            ));
+        }
+        Some(report)
+    }
+
+    /// Formats the redaction report for display, or returns `None` if nothing
+    /// was redacted.
+    pub fn redaction_report(&self) -> Option<String> {
+        if self.redactions.is_empty() {
+            return None;
+        }
+
+        let noun = if self.redactions.len() == 1 {
+            "secret"
+        } else {
+            "secrets"
+        };
+        let mut report = format!("🔒 Redacted {} {noun}:\n", self.redactions.len());
+        for entry in &self.redactions {
+            report.push_str(&format!(
+                "  {}:{} [{}]\n",
+                entry.path.display(),
+                entry.line,
+                entry.kind
+            ));
+        }
+        Some(report)
+    }
 }
 
 impl OutputGenerator {
@@ -57,7 +221,41 @@ impl OutputGenerator {
     /// let generator = OutputGenerator::new();
     /// ```
     pub fn new() -> Self {
-        Self {}
+        Self { progress: None }
+    }
+
+    /// Calls `progress` as files are read and rendered, e.g. to draw a progress bar.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tree_txt::output_generator::Progress;
+    /// use tree_txt::{OutputFormat, OutputGenerator};
+    /// use std::path::{Path, PathBuf};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let seen = Arc::new(Mutex::new(Vec::new()));
+    /// let recorder = Arc::clone(&seen);
+    /// let generator = OutputGenerator::new()
+    ///     .with_progress(move |progress| recorder.lock().unwrap().push(progress));
+    ///
+    /// let files = vec![PathBuf::from("Cargo.toml")];
+    /// generator.render(Path::new("."), &files, &OutputFormat::default())?;
+    ///
+    /// let seen = seen.lock().unwrap();
+    /// assert!(seen.contains(&Progress::Reading { done: 1, total: 1 }));
+    /// assert_eq!(seen.last(), Some(&Progress::Finished));
+    /// # Ok::<(), tree_txt::TreeTxtError>(())
+    /// ```
+    pub fn with_progress(mut self, progress: impl Fn(Progress) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    fn report_progress(&self, progress: Progress) {
+        if let Some(callback) = &self.progress {
+            callback(progress);
+        }
     }
 
     /// Generates a formatted text export from the selected files.
@@ -81,14 +279,15 @@ impl OutputGenerator {
     /// let generator = OutputGenerator::new();
     /// let config = OutputFormat::default();
     /// let files = vec![Path::new("README.md").to_path_buf()];
+    /// let output = std::env::temp_dir().join("tree-txt-doc-output.txt");
     ///
     /// generator.generate_with_config(
     ///     Path::new("."),
     ///     &files,
-    ///     "output.txt",
+    ///     output.to_str().unwrap(),
     ///     &config,
     /// )?;
-    /// # Ok::<(), anyhow::Error>(())
+    /// # Ok::<(), tree_txt::TreeTxtError>(())
     /// ```
     ///
     /// # Errors
@@ -98,36 +297,328 @@ impl OutputGenerator {
     /// - The output file cannot be written
     /// - Directory traversal fails
     /// - Insufficient permissions
+    /// - A custom redaction pattern is not a valid regular expression
     pub fn generate_with_config(
         &self,
         base_path: &Path,
         selected_files: &[PathBuf],
         output_file: &str,
         config: &OutputFormat,
-    ) -> Result<()> {
-        let mut content = String::new();
+    ) -> Result<ExportReport> {
+        self.generate_workspace(
+            &Workspace::single(base_path),
+            selected_files,
+            output_file,
+            config,
+        )
+    }
+
+    /// Generates an export of files from one or more root directories.
+    ///
+    /// With several roots, the directory tree gets one labeled section per
+    /// root and `File:` banners show paths as `label/relative/path`.
+    ///
+    /// When the export is split into parts, `output.part1.txt` and so on are
+    /// written instead of `output_file`. Parts numbered above the new part
+    /// count, left over from an earlier export, are deleted and listed in
+    /// [`ExportReport::stale_parts_removed`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use tree_txt::project::Workspace;
+    /// use tree_txt::{OutputGenerator, OutputFormat};
+    /// use std::path::PathBuf;
+    ///
+    /// let workspace = Workspace::new(vec![PathBuf::from("../client"), PathBuf::from("../server")]);
+    /// let files = vec![
+    ///     PathBuf::from("../client/src/api.ts"),
+    ///     PathBuf::from("../server/src/routes.rs"),
+    /// ];
+    ///
+    /// OutputGenerator::new().generate_workspace(
+    ///     &workspace,
+    ///     &files,
+    ///     "output.txt",
+    ///     &OutputFormat::default(),
+    /// )?;
+    /// # Ok::<(), tree_txt::TreeTxtError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as
+    /// [`generate_with_config`](Self::generate_with_config).
+    pub fn generate_workspace(
+        &self,
+        workspace: &Workspace,
+        selected_files: &[PathBuf],
+        output_file: &str,
+        config: &OutputFormat,
+    ) -> Result<ExportReport> {
+        let (sections, mut report) = self.render_sections(workspace, selected_files, config)?;
+
+        let mut outputs = Vec::new();
+        if let Some(limit) = config.max_part_size {
+            let parts = chunker::split_into_parts(&sections, limit, config.part_size_unit);
+            if parts.len() > 1 {
+                for (index, part) in parts.into_iter().enumerate() {
+                    outputs.push((part_path(output_file, index + 1), part.text));
+                }
+            }
+        }
+        if outputs.is_empty() {
+            outputs.push((PathBuf::from(output_file), join_sections(&sections)));
+        }
+
+        // Write to file with better error handling
+        for (path, content) in outputs {
+            fs::write(&path, content).map_err(|source| TreeTxtError::WriteFile {
+                path: path.clone(),
+                source,
+            })?;
+            report.files_written.push(path);
+        }
+
+        // Parts beyond the new count are left over from an earlier, larger export
+        let first_stale = if report.files_written.len() > 1 {
+            report.files_written.len() + 1
+        } else {
+            1
+        };
+        for index in first_stale.. {
+            let path = part_path(output_file, index);
+            if !path.is_file() {
+                break;
+            }
+            fs::remove_file(&path).map_err(|source| TreeTxtError::WriteFile {
+                path: path.clone(),
+                source,
+            })?;
+            report.stale_parts_removed.push(path);
+        }
+
+        Ok(report)
+    }
+
+    /// Renders the export to a string instead of writing it to a file.
+    ///
+    /// Part splitting (`max_part_size`) is not applied.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tree_txt::{OutputGenerator, OutputFormat};
+    /// use std::path::Path;
+    ///
+    /// let generator = OutputGenerator::new();
+    /// let files = vec![Path::new("Cargo.toml").to_path_buf()];
+    /// let (text, _report) = generator.render(Path::new("."), &files, &OutputFormat::default())?;
+    ///
+    /// assert!(text.contains("File: Cargo.toml"));
+    /// # Ok::<(), tree_txt::TreeTxtError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as
+    /// [`generate_with_config`](Self::generate_with_config), except writing.
+    pub fn render(
+        &self,
+        base_path: &Path,
+        selected_files: &[PathBuf],
+        config: &OutputFormat,
+    ) -> Result<(String, ExportReport)> {
+        self.render_workspace(&Workspace::single(base_path), selected_files, config)
+    }
+
+    /// Renders an export of files from one or more root directories to a
+    /// string. See [`generate_workspace`](Self::generate_workspace).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as [`render`](Self::render).
+    pub fn render_workspace(
+        &self,
+        workspace: &Workspace,
+        selected_files: &[PathBuf],
+        config: &OutputFormat,
+    ) -> Result<(String, ExportReport)> {
+        let (sections, report) = self.render_sections(workspace, selected_files, config)?;
+        Ok((join_sections(&sections), report))
+    }
+
+    fn render_sections(
+        &self,
+        workspace: &Workspace,
+        selected_files: &[PathBuf],
+        config: &OutputFormat,
+    ) -> Result<(Vec<Section>, ExportReport)> {
+        let _finish = FinishGuard(self);
+        let mut report = ExportReport::default();
+
+        let (kept_files, contents) =
+            self.read_files(workspace, selected_files, config, &mut report)?;
+        let selected_files = kept_files.as_slice();
+
+        // Collect metadata up front so each file is only inspected once, and
+        // count lines in the contents already read
+        let metadata = if config.shows_metadata() || config.include_summary {
+            self.collect_metadata(selected_files, &contents)
+        } else {
+            HashMap::new()
+        };
 
         // Add header
-        content.push_str(&self.generate_header(base_path, selected_files));
+        let mut preamble = self.generate_header(workspace, selected_files, config, &metadata);
 
         // Add directory tree if requested
         if config.include_tree {
-            content.push_str(&self.generate_tree(base_path, selected_files)?);
+            if workspace.is_multi_root() {
+                preamble.push_str(&self.generate_workspace_tree(
+                    workspace,
+                    selected_files,
+                    config,
+                    &metadata,
+                )?);
+            } else {
+                preamble.push_str(&self.generate_tree(
+                    workspace.primary(),
+                    selected_files,
+                    config,
+                    &metadata,
+                )?);
+            }
         }
 
+        let mut sections = vec![Section::other(preamble)];
+
         // Add file contents if requested
         if config.include_file_contents {
-            content.push_str(&self.generate_file_contents(base_path, selected_files, config)?);
+            sections.extend(self.generate_file_contents(
+                workspace,
+                selected_files,
+                &contents,
+                config,
+                &metadata,
+                &mut report,
+            )?);
         }
 
-        // Write to file with better error handling
-        fs::write(output_file, content)
-            .map_err(|e| anyhow::anyhow!("Cannot write to output file '{}': {}", output_file, e))?;
+        Ok((sections, report))
+    }
 
-        Ok(())
+    /// Reads every selected file, applying the configured [`ErrorPolicy`] to
+    /// files that cannot be read.
+    ///
+    /// Returns the files that stay in the export, with each file's contents or,
+    /// under [`ErrorPolicy::Embed`], the error message to embed in its place.
+    ///
+    /// Without file contents in the export, a file only has to exist. Its
+    /// contents are still read for line counts if the metadata or summary
+    /// shows them, but failing to read or decode them is not a problem.
+    fn read_files(
+        &self,
+        workspace: &Workspace,
+        selected_files: &[PathBuf],
+        config: &OutputFormat,
+        report: &mut ExportReport,
+    ) -> Result<(Vec<PathBuf>, FileContents)> {
+        let mut kept_files = Vec::new();
+        let mut contents = FileContents::new();
+        let overrides = EncodingOverrides::new(&config.encodings)?;
+
+        // Read in parallel, then apply the error policy in selection order
+        let needs_line_counts = config.show_line_count || config.include_summary;
+        let total = selected_files.len();
+        let done = AtomicUsize::new(0);
+        let results: Vec<std::io::Result<Option<Decoded>>> = selected_files
+            .par_iter()
+            .map(|file_path| {
+                let relative_path = workspace
+                    .root_of(file_path)
+                    .and_then(|root| file_path.strip_prefix(&root.path).ok())
+                    .unwrap_or(file_path);
+                let forced = overrides.encoding_for(relative_path);
+
+                let decode =
+                    || fs::read(file_path).and_then(|bytes| encoding::decode(&bytes, forced));
+                let read = if config.include_file_contents {
+                    decode().map(Some)
+                } else {
+                    ensure_file(file_path)
+                        .map(|()| needs_line_counts.then(|| decode().ok()).flatten())
+                };
+                let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+                self.report_progress(Progress::Reading { done, total });
+                read
+            })
+            .collect();
+
+        for (file_path, read) in selected_files.iter().zip(results) {
+            let error = match read {
+                Ok(content) => {
+                    kept_files.push(file_path.clone());
+                    if let Some(content) = content {
+                        contents.insert(file_path.clone(), Ok(content));
+                    }
+                    continue;
+                }
+                Err(error) => error,
+            };
+
+            if config.on_error == ErrorPolicy::Fail {
+                return Err(TreeTxtError::ReadFile {
+                    path: file_path.clone(),
+                    source: error,
+                });
+            }
+
+            report.problems.push(Problem {
+                path: workspace.display_path(file_path),
+                kind: ProblemKind::from_io(&error),
+                message: error.to_string(),
+            });
+
+            if config.on_error == ErrorPolicy::Embed {
+                kept_files.push(file_path.clone());
+                contents.insert(file_path.clone(), Err(error.to_string()));
+            }
+        }
+
+        if kept_files.is_empty() && !selected_files.is_empty() {
+            return Err(TreeTxtError::NoFilesSelected);
+        }
+
+        Ok((kept_files, contents))
+    }
+
+    fn collect_metadata(
+        &self,
+        selected_files: &[PathBuf],
+        contents: &FileContents,
+    ) -> HashMap<PathBuf, FileMetadata> {
+        selected_files
+            .par_iter()
+            .filter_map(|path| {
+                let text = match contents.get(path) {
+                    Some(Ok(decoded)) => Some(decoded.text.as_str()),
+                    _ => None,
+                };
+                FileMetadata::from_path(path, text)
+                    .ok()
+                    .map(|metadata| (path.clone(), metadata))
+            })
+            .collect()
     }
 
-    fn generate_header(&self, base_path: &Path, selected_files: &[PathBuf]) -> String {
+    fn generate_header(
+        &self,
+        workspace: &Workspace,
+        selected_files: &[PathBuf],
+        config: &OutputFormat,
+        metadata: &HashMap<PathBuf, FileMetadata>,
+    ) -> String {
         let mut header = String::new();
 
         header.push_str("# Codebase Export\n");
@@ -135,8 +626,26 @@ impl OutputGenerator {
             "Generated on: {}\n",
             chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
         ));
-        header.push_str(&format!("Base directory: {}\n", base_path.display()));
-        header.push_str(&format!("Total files: {}\n\n", selected_files.len()));
+        if workspace.is_multi_root() {
+            header.push_str("Base directories:\n");
+            for root in workspace.roots() {
+                header.push_str(&format!("  {}: {}\n", root.label, root.path.display()));
+            }
+        } else {
+            header.push_str(&format!(
+                "Base directory: {}\n",
+                workspace.primary().display()
+            ));
+        }
+        header.push_str(&format!("Total files: {}\n", selected_files.len()));
+        if let Some(note) = self.describe_transforms(config) {
+            header.push_str(&format!("Transforms: {note}\n"));
+        }
+        header.push('\n');
+
+        if config.include_summary {
+            header.push_str(&self.generate_summary(selected_files, metadata));
+        }
 
         let separator = "═".repeat(80);
         header.push_str(&format!("{separator}\n"));
@@ -146,7 +655,90 @@ impl OutputGenerator {
         header
     }
 
-    fn generate_tree(&self, base_path: &Path, selected_files: &[PathBuf]) -> Result<String> {
+    fn is_outlined(&self, workspace: &Workspace, file_path: &Path, config: &OutputFormat) -> bool {
+        config.outline_mode
+            || config.outline_files.iter().any(|path| {
+                path == file_path
+                    || workspace
+                        .roots()
+                        .iter()
+                        .any(|root| root.path.join(path) == file_path)
+            })
+    }
+
+    fn describe_transforms(&self, config: &OutputFormat) -> Option<String> {
+        let global = config.transforms();
+        let normalization = config.normalization();
+        let overridden: Vec<String> = config
+            .transform_overrides
+            .iter()
+            .filter(|(_, overrides)| overrides.apply_to(global) != global)
+            .map(|(extension, _)| format!(".{}", extension.trim_start_matches('.')))
+            .collect();
+
+        if !global.any() && !normalization.any() && overridden.is_empty() {
+            return None;
+        }
+
+        let mut names = normalization.names();
+        names.extend(global.names().into_iter().map(str::to_string));
+        let mut note = if names.is_empty() {
+            "none".to_string()
+        } else {
+            names.join(", ")
+        };
+        if !overridden.is_empty() {
+            note.push_str(&format!(
+                " (per-extension overrides for {})",
+                overridden.join(", ")
+            ));
+        }
+        Some(note)
+    }
+
+    fn generate_summary(
+        &self,
+        selected_files: &[PathBuf],
+        metadata: &HashMap<PathBuf, FileMetadata>,
+    ) -> String {
+        let mut by_language: BTreeMap<&str, SummaryRow> = BTreeMap::new();
+        let mut by_extension: BTreeMap<String, SummaryRow> = BTreeMap::new();
+
+        for file_path in selected_files {
+            let Some(file_metadata) = metadata.get(file_path) else {
+                continue;
+            };
+
+            let extension = file_path
+                .extension()
+                .map(|ext| format!(".{}", ext.to_string_lossy()))
+                .unwrap_or_else(|| "(none)".to_string());
+
+            by_language
+                .entry(file_metadata.language.name())
+                .or_default()
+                .add(file_metadata);
+            by_extension
+                .entry(extension)
+                .or_default()
+                .add(file_metadata);
+        }
+
+        let mut summary = String::new();
+        summary.push_str(&format_summary_table("Language", &by_language));
+        summary.push('\n');
+        summary.push_str(&format_summary_table("Extension", &by_extension));
+        summary.push('\n');
+        summary
+    }
+
+    fn generate_tree(
+        &self,
+        base_path: &Path,
+        selected_files: &[PathBuf],
+        config: &OutputFormat,
+        metadata: &HashMap<PathBuf, FileMetadata>,
+    ) -> Result<String> {
         let mut tree_content = String::new();
         let mut paths_set: BTreeSet<PathBuf> = BTreeSet::new();
 
@@ -181,11 +773,22 @@ impl OutputGenerator {
             let indent = "    ".repeat(depth);
             let name = path.file_name().unwrap_or_default().to_string_lossy();
 
-            if selected_files
+            if let Some(file_path) = selected_files
                 .iter()
-                .any(|f| f.strip_prefix(base_path).unwrap_or(f) == path)
+                .find(|f| f.strip_prefix(base_path).unwrap_or(f) == path)
             {
-                tree_content.push_str(&format!("{indent}├── {name} ✓\n"));
+                let annotations = metadata
+                    .get(file_path)
+                    .map(|m| m.annotations(config))
+                    .unwrap_or_default();
+                if annotations.is_empty() {
+                    tree_content.push_str(&format!("{indent}├── {name} ✓\n"));
+                } else {
+                    tree_content.push_str(&format!(
+                        "{indent}├── {name} ✓ [{}]\n",
+                        annotations.join(", ")
+                    ));
+                }
             } else if base_path.join(path).is_dir() {
                 tree_content.push_str(&format!("{indent}├── {name}/\n"));
             }
@@ -195,62 +798,397 @@ impl OutputGenerator {
         Ok(tree_content)
     }
 
-    fn generate_file_contents(
+    /// Generates one labeled tree section per root of a multi-root workspace.
+    fn generate_workspace_tree(
         &self,
-        base_path: &Path,
+        workspace: &Workspace,
         selected_files: &[PathBuf],
         config: &OutputFormat,
+        metadata: &HashMap<PathBuf, FileMetadata>,
     ) -> Result<String> {
-        let mut content = String::new();
+        let mut tree_content = String::new();
+
+        for (index, root) in workspace.roots().iter().enumerate() {
+            let root_files: Vec<PathBuf> = selected_files
+                .iter()
+                .filter(|file| workspace.root_index(file) == index)
+                .cloned()
+                .collect();
+            tree_content.push_str(&format!("### {} ({})\n", root.label, root.path.display()));
+            tree_content.push_str(&self.generate_tree(
+                &root.path,
+                &root_files,
+                config,
+                metadata,
+            )?);
+        }
+
+        let outside: Vec<PathBuf> = selected_files
+            .iter()
+            .filter(|file| workspace.root_of(file).is_none())
+            .cloned()
+            .collect();
+        if !outside.is_empty() {
+            tree_content.push_str("### Outside workspace roots\n");
+            for file in outside {
+                tree_content.push_str(&format!("├── {} ✓\n", file.display()));
+            }
+            tree_content.push('\n');
+        }
+
+        Ok(tree_content)
+    }
+
+    fn generate_file_contents(
+        &self,
+        workspace: &Workspace,
+        selected_files: &[PathBuf],
+        contents: &FileContents,
+        config: &OutputFormat,
+        metadata: &HashMap<PathBuf, FileMetadata>,
+        report: &mut ExportReport,
+    ) -> Result<Vec<Section>> {
+        let mut sections = Vec::new();
+
+        let redactor = if config.redact_secrets {
+            Some(
+                Redactor::new(&config.redaction_patterns)?
+                    .with_high_entropy(config.redact_high_entropy),
+            )
+        } else {
+            None
+        };
+
+        if !config.line_number_format.contains("{n}") {
+            return Err(TreeTxtError::ConfigError(format!(
+                "line_number_format '{}' must contain {{n}}",
+                config.line_number_format
+            )));
+        }
 
         let separator = "═".repeat(80);
-        content.push_str(&format!("{separator}\n"));
-        content.push_str("## FILE CONTENTS\n");
-        content.push_str(&format!("{separator}\n\n"));
+        sections.push(Section::other(format!(
+            "{separator}\n## FILE CONTENTS\n{separator}\n\n"
+        )));
 
-        let mut sorted_files = selected_files.to_vec();
-        sorted_files.sort();
+        let sorted_files =
+            ordering::sort_files(workspace, selected_files, config.sort_order, |path| {
+                contents
+                    .get(path)
+                    .and_then(|content| content.as_ref().ok())
+                    .map(|decoded| decoded.text.as_str())
+            });
 
-        for (index, file_path) in sorted_files.iter().enumerate() {
+        let total = sorted_files.len();
+        let done = AtomicUsize::new(0);
+        let rendered: Vec<(String, Vec<RedactionEntry>)> = sorted_files
+            .par_iter()
+            .map(|file_path| {
+                let rendered = self.render_file(
+                    workspace,
+                    file_path,
+                    contents,
+                    config,
+                    metadata,
+                    redactor.as_ref(),
+                );
+                let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+                self.report_progress(Progress::Rendering { done, total });
+                rendered
+            })
+            .collect();
+
+        // Assemble in sorted order, whatever order the files finished in
+        for (index, (file_path, (text, redactions))) in
+            sorted_files.iter().zip(rendered).enumerate()
+        {
+            let mut content = String::new();
             if index > 0 {
                 content.push('\n');
             }
+            content.push_str(&text);
+            report.redactions.extend(redactions);
 
-            let relative_path = file_path.strip_prefix(base_path).unwrap_or(file_path);
+            let relative_path = workspace.display_path(file_path);
+            sections.push(Section::file(relative_path.display().to_string(), content));
+        }
+
+        Ok(sections)
+    }
+
+    /// Renders the banner and contents of one file, returning them with the
+    /// secrets redacted from it.
+    fn render_file(
+        &self,
+        workspace: &Workspace,
+        file_path: &Path,
+        contents: &FileContents,
+        config: &OutputFormat,
+        metadata: &HashMap<PathBuf, FileMetadata>,
+        redactor: Option<&Redactor>,
+    ) -> (String, Vec<RedactionEntry>) {
+        let normalization = config.normalization();
+        let mut redactions = Vec::new();
+        let relative_path = workspace.display_path(file_path);
+
+        // File header
+        let mut content = String::new();
+        let file_separator = "─".repeat(60);
+        content.push_str(&format!("{file_separator}\n"));
+        content.push_str(&format!("File: {}\n", relative_path.display()));
+        let language = Language::detect(file_path);
+        // Excerpts skip transforms that drop lines, so their numbers stay accurate
+        let ranges = config
+            .line_ranges
+            .get(file_path)
+            .map(|ranges| line_range::merge(ranges))
+            .filter(|ranges| !ranges.is_empty());
+        let outline_parser = if ranges.is_none() && self.is_outlined(workspace, file_path, config) {
+            outline::parser_for(language)
+        } else {
+            None
+        };
+
+        // File contents
+        let mut lines_note = None;
+        let body = match &contents[file_path] {
+            Ok(Decoded {
+                text: file_content, ..
+            }) => {
+                // Normalize first so redaction reports and line numbers agree with the export
+                let file_content = if normalization.any() {
+                    transform::normalize(file_content, &normalization)
+                } else {
+                    file_content.clone()
+                };
+                let line_count = file_content.split_inclusive('\n').count();
+
+                // Redact before any other transform so reported line numbers match the file
+                let file_content = match redactor {
+                    Some(redactor) => {
+                        let (redacted, findings) = redactor.redact(file_path, &file_content);
+                        let exported = |line: usize| {
+                            ranges.as_ref().is_none_or(|ranges| {
+                                ranges.iter().any(|range| {
+                                    line >= range.start && line <= range.end_within(line_count)
+                                })
+                            })
+                        };
+                        redactions.extend(
+                            findings
+                                .into_iter()
+                                .filter(|finding| exported(finding.line))
+                                .map(|finding| RedactionEntry {
+                                    path: relative_path.clone(),
+                                    line: finding.line,
+                                    kind: finding.kind,
+                                }),
+                        );
+                        redacted
+                    }
+                    None => file_content,
+                };
+
+                let transforms = config.transforms_for(file_path);
+                if let Some(ranges) = &ranges {
+                    let shown: Vec<String> = ranges
+                        .iter()
+                        .map(|range| {
+                            if range.start <= line_count {
+                                let end = range.end_within(line_count);
+                                LineRange::new(range.start, Some(end)).to_string()
+                            } else {
+                                range.to_string()
+                            }
+                        })
+                        .collect();
+                    lines_note = Some(format!("{} of {line_count}", shown.join(", ")));
+                    render_excerpts(
+                        &file_content,
+                        ranges,
+                        transforms.trim_trailing_whitespace,
+                        &config.line_number_format,
+                    )
+                } else {
+                    let file_content = match outline_parser {
+                        Some(parser) => parser.outline(&file_content),
+                        None => file_content,
+                    };
 
-            // File header
-            let file_separator = "─".repeat(60);
-            content.push_str(&format!("{file_separator}\n"));
-            content.push_str(&format!("File: {}\n", relative_path.display()));
-            content.push_str(&format!("{file_separator}\n\n"));
+                    let file_content = if transforms.any() {
+                        transform::apply(&file_content, language, &transforms)
+                    } else {
+                        file_content
+                    };
 
-            // File contents
-            match fs::read_to_string(file_path) {
-                Ok(file_content) => {
                     if file_content.trim().is_empty() {
-                        content.push_str("(empty file)\n");
+                        "(empty file)\n".to_string()
+                    } else if config.include_line_numbers {
+                        number_lines(&file_content, &config.line_number_format)
+                    } else if file_content.ends_with('\n') {
+                        file_content
                     } else {
-                        // Add content with or without line numbers based on config
-                        if config.include_line_numbers {
-                            for (line_num, line) in file_content.lines().enumerate() {
-                                content.push_str(&format!("{:4} | {}\n", line_num + 1, line));
-                            }
-                        } else {
-                            content.push_str(&file_content);
-                            if !file_content.ends_with('\n') {
-                                content.push('\n');
-                            }
-                        }
+                        file_content + "\n"
                     }
                 }
-                Err(e) => {
-                    content.push_str(&format!("Error reading file: {e}\n"));
-                }
             }
+            Err(e) => format!("Error reading file: {e}\n"),
+        };
+
+        if outline_parser.is_some() {
+            content.push_str("Mode: outline (function bodies elided)\n");
+        }
+        if let Some(note) = lines_note {
+            content.push_str(&format!("Lines: {note}\n"));
+        }
+        if let Ok(decoded) = &contents[file_path]
+            && let Some(note) = decoded.banner_note()
+        {
+            content.push_str(&format!("Encoding: {note}\n"));
+        }
+        if let Some(file_metadata) = metadata.get(file_path) {
+            let annotations = file_metadata.annotations(config);
+            if !annotations.is_empty() {
+                content.push_str(&format!("Info: {}\n", annotations.join(" | ")));
+            }
+        }
+        content.push_str(&format!("{file_separator}\n\n"));
+        content.push_str(&body);
+
+        (content, redactions)
+    }
+}
+
+/// Minimum width of the line-number column.
+const MIN_LINE_NUMBER_WIDTH: usize = 4;
+
+/// Renders one numbered line, with `{n}` in `format` replaced by `number`
+/// right-aligned to `width`.
+fn gutter_line(format: &str, number: impl std::fmt::Display, width: usize, line: &str) -> String {
+    let number = format!("{number:>width$}");
+    format!("{}{line}\n", format.replace("{n}", &number))
+}
+
+fn line_number_width(last_line: usize) -> usize {
+    last_line.to_string().len().max(MIN_LINE_NUMBER_WIDTH)
+}
+
+/// Checks that `path` exists and is a regular file, without reading it.
+fn ensure_file(path: &Path) -> std::io::Result<()> {
+    if fs::metadata(path)?.is_file() {
+        Ok(())
+    } else {
+        Err(std::io::ErrorKind::IsADirectory.into())
+    }
+}
+
+/// Numbers every line of `content`, keeping any `\r` so un-normalized line
+/// endings survive numbering.
+fn number_lines(content: &str, format: &str) -> String {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let width = line_number_width(lines.len());
+
+    lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let line = line.strip_suffix('\n').unwrap_or(line);
+            gutter_line(format, index + 1, width, line)
+        })
+        .collect()
+}
+
+/// Renders the lines of `content` within `ranges` (sorted and merged) with
+/// their original line numbers, marking skipped lines with `...`.
+fn render_excerpts(content: &str, ranges: &[LineRange], trim: bool, format: &str) -> String {
+    let lines: Vec<&str> = content
+        .split_inclusive('\n')
+        .map(|line| line.strip_suffix('\n').unwrap_or(line))
+        .collect();
+    let line_count = lines.len();
+
+    let shown: Vec<(usize, usize)> = ranges
+        .iter()
+        .filter(|range| range.start <= line_count)
+        .map(|range| (range.start, range.end_within(line_count)))
+        .collect();
+    let Some(&(_, last_shown)) = shown.last() else {
+        return format!(
+            "(the selected lines are beyond the end of the file, which has {line_count} lines)\n"
+        );
+    };
+
+    let width = line_number_width(last_shown);
+    let mut excerpt = String::new();
+    let mut next_line = 1;
+    for (start, end) in shown {
+        if start > next_line {
+            excerpt.push_str(&gutter_line(format, "...", width, ""));
+        }
+        for number in start..=end {
+            let line = lines[number - 1];
+            let line = if trim { line.trim_end() } else { line };
+            excerpt.push_str(&gutter_line(format, number, width, line));
         }
+        next_line = end + 1;
+    }
+    if next_line <= line_count {
+        excerpt.push_str(&gutter_line(format, "...", width, ""));
+    }
+
+    excerpt
+}
+
+fn join_sections(sections: &[Section]) -> String {
+    sections
+        .iter()
+        .map(|section| section.text.as_str())
+        .collect()
+}
+
+/// Returns the path of part `index` of a chunked export, e.g.
+/// `codebase.txt` becomes `codebase.part1.txt`.
+fn part_path(output_file: &str, index: usize) -> PathBuf {
+    let path = Path::new(output_file);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let file_name = match path.extension() {
+        Some(extension) => format!("{stem}.part{index}.{}", extension.to_string_lossy()),
+        None => format!("{stem}.part{index}"),
+    };
+    path.with_file_name(file_name)
+}
 
-        Ok(content)
+/// Aggregated totals for one row of the header summary table.
+#[derive(Default)]
+struct SummaryRow {
+    files: usize,
+    lines: usize,
+    bytes: u64,
+}
+
+impl SummaryRow {
+    fn add(&mut self, metadata: &FileMetadata) {
+        self.files += 1;
+        self.lines += metadata.lines;
+        self.bytes += metadata.size;
+    }
+}
+
+fn format_summary_table<K: AsRef<str>>(label: &str, rows: &BTreeMap<K, SummaryRow>) -> String {
+    let mut table = format!("{label:<16} {:>7} {:>9} {:>12}\n", "Files", "Lines", "Size");
+    table.push_str(&format!("{}\n", "─".repeat(47)));
+
+    for (key, row) in rows {
+        table.push_str(&format!(
+            "{:<16} {:>7} {:>9} {:>12}\n",
+            key.as_ref(),
+            row.files,
+            row.lines,
+            format_size(row.bytes)
+        ));
     }
+
+    table
 }
 
 impl Default for OutputGenerator {
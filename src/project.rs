@@ -0,0 +1,164 @@
+//! # Project Root Detection
+//!
+//! Finds the root directory of the project containing a path, so Tree-TXT can
+//! be started from any subdirectory, and groups several roots into a
+//! [`Workspace`] for multi-repository exports.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use tree_txt::project::find_project_root;
+//!
+//! let root = std::env::temp_dir().join(format!("tree-txt-root-doc-{}", std::process::id()));
+//! let nested = root.join("src").join("bin");
+//! std::fs::create_dir_all(&nested)?;
+//! std::fs::write(root.join("Cargo.toml"), "[package]\n")?;
+//!
+//! assert_eq!(find_project_root(&nested), Some(root.clone()));
+//!
+//! std::fs::remove_dir_all(&root)?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use std::path::{Path, PathBuf};
+
+/// Files and directories that mark the root of a project.
+pub const ROOT_MARKERS: &[&str] = &[".git", "Cargo.toml", "package.json"];
+
+/// Walks up from `start` and returns the nearest directory containing one of
+/// the [`ROOT_MARKERS`], or `None` if no ancestor has one.
+pub fn find_project_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| ROOT_MARKERS.iter().any(|marker| dir.join(marker).exists()))
+        .map(Path::to_path_buf)
+}
+
+/// A project root directory and the label that identifies it in exports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Root {
+    /// Short name used in tree sections and `File:` banners
+    pub label: String,
+    /// Directory the root's files live in
+    pub path: PathBuf,
+}
+
+/// The set of root directories files are selected from.
+///
+/// A workspace with a single root behaves exactly like a plain base
+/// directory: paths are shown relative to it. With several roots, each path
+/// is prefixed with its root's label, e.g. `client/src/main.ts` and
+/// `server/src/main.rs`.
+///
+/// # Examples
+///
+/// ```rust
+/// use tree_txt::project::Workspace;
+/// use std::path::{Path, PathBuf};
+///
+/// let workspace = Workspace::new(vec![
+///     PathBuf::from("/code/client"),
+///     PathBuf::from("/code/server"),
+/// ]);
+///
+/// assert!(workspace.is_multi_root());
+/// assert_eq!(
+///     workspace.display_path(Path::new("/code/server/src/main.rs")),
+///     PathBuf::from("server/src/main.rs")
+/// );
+///
+/// let single = Workspace::single(Path::new("/code/server"));
+/// assert_eq!(
+///     single.display_path(Path::new("/code/server/src/main.rs")),
+///     PathBuf::from("src/main.rs")
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workspace {
+    roots: Vec<Root>,
+}
+
+impl Workspace {
+    /// Creates a workspace with one root.
+    pub fn single(path: &Path) -> Self {
+        Self::new(vec![path.to_path_buf()])
+    }
+
+    /// Creates a workspace from several root directories, in display order.
+    ///
+    /// Roots are labelled with their directory names; clashing names get a
+    /// numeric suffix (`app`, `app-2`). Duplicate paths are ignored.
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        let mut roots: Vec<Root> = Vec::new();
+
+        for path in paths {
+            if roots.iter().any(|root| root.path == path) {
+                continue;
+            }
+
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string());
+            let mut label = name.clone();
+            let mut suffix = 2;
+            while roots.iter().any(|root| root.label == label) {
+                label = format!("{name}-{suffix}");
+                suffix += 1;
+            }
+
+            roots.push(Root { label, path });
+        }
+
+        Self { roots }
+    }
+
+    /// Returns the roots in display order.
+    pub fn roots(&self) -> &[Root] {
+        &self.roots
+    }
+
+    /// Returns the first root, used where a single directory is needed.
+    pub fn primary(&self) -> &Path {
+        self.roots.first().map_or(Path::new("."), |root| &root.path)
+    }
+
+    /// Returns `true` if the workspace has more than one root.
+    pub fn is_multi_root(&self) -> bool {
+        self.roots.len() > 1
+    }
+
+    /// Returns the innermost root containing `path`.
+    pub fn root_of(&self, path: &Path) -> Option<&Root> {
+        self.roots
+            .iter()
+            .filter(|root| path.starts_with(&root.path))
+            .max_by_key(|root| root.path.components().count())
+    }
+
+    /// Returns the index of the root containing `path`, or the number of
+    /// roots if no root contains it. Useful for grouping files by root.
+    pub fn root_index(&self, path: &Path) -> usize {
+        self.root_of(path)
+            .and_then(|found| self.roots.iter().position(|root| root == found))
+            .unwrap_or(self.roots.len())
+    }
+
+    /// Returns `path` as shown in exports: relative to its root and, in a
+    /// multi-root workspace, prefixed with the root's label.
+    ///
+    /// Paths outside every root are returned unchanged.
+    pub fn display_path(&self, path: &Path) -> PathBuf {
+        match self.root_of(path) {
+            Some(root) => {
+                let relative = path.strip_prefix(&root.path).unwrap_or(path);
+                if self.is_multi_root() {
+                    Path::new(&root.label).join(relative)
+                } else {
+                    relative.to_path_buf()
+                }
+            }
+            None => path.to_path_buf(),
+        }
+    }
+}
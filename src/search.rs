@@ -0,0 +1,173 @@
+//! # Content Search
+//!
+//! Finds the files whose contents match a query, e.g. every file that
+//! mentions `StateManager`, so they can be selected in one go.
+//!
+//! The search walks the workspace roots the way `git` and `ripgrep` see them:
+//! files excluded by `.gitignore`, `.ignore` or the global git excludes are
+//! skipped, as are hidden files unless requested. Binary files never match.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use tree_txt::line_range::LineRange;
+//! use tree_txt::project::Workspace;
+//! use tree_txt::search::{Query, search};
+//!
+//! let dir = std::env::temp_dir().join(format!("tree-txt-search-doc-{}", std::process::id()));
+//! std::fs::create_dir_all(dir.join("target"))?;
+//! std::fs::write(dir.join(".gitignore"), "target/\n")?;
+//! std::fs::write(dir.join("main.rs"), "use state::StateManager;\n\nfn main() {\n    StateManager::new();\n}\n")?;
+//! std::fs::write(dir.join("lib.rs"), "pub mod state;\n")?;
+//! std::fs::write(dir.join("target/out.rs"), "StateManager\n")?;
+//!
+//! let query = Query::new("StateManager", true, false)?;
+//! let matches = search(&Workspace::single(&dir), &query, false);
+//! assert_eq!(matches.len(), 1);
+//! assert_eq!(matches[0].path, dir.join("main.rs"));
+//! assert_eq!(matches[0].hits, 2);
+//! assert_eq!(matches[0].lines, vec![1, 4]);
+//! assert_eq!(matches[0].regions(1), vec![LineRange::new(1, Some(5))]);
+//!
+//! // Empty matches are not hits, so patterns that can match nothing still work
+//! let query = Query::new("(Manager)?", false, false)?;
+//! let matches = search(&Workspace::single(&dir), &query, false);
+//! assert_eq!(matches.len(), 1);
+//! assert_eq!(matches[0].hits, 2);
+//!
+//! std::fs::remove_dir_all(&dir)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::encoding;
+use crate::error::Result;
+use crate::line_range::{self, LineRange};
+use crate::project::Workspace;
+use ignore::WalkBuilder;
+use rayon::prelude::*;
+use regex::{Regex, RegexBuilder};
+use std::fs;
+use std::path::PathBuf;
+
+/// A compiled search query.
+#[derive(Debug, Clone)]
+pub struct Query {
+    regex: Regex,
+}
+
+impl Query {
+    /// Compiles `pattern` as a regular expression, or as plain text if
+    /// `literal` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TreeTxtError::InvalidPattern`](crate::TreeTxtError::InvalidPattern)
+    /// if `pattern` is not a valid regular expression.
+    pub fn new(pattern: &str, literal: bool, ignore_case: bool) -> Result<Self> {
+        let pattern = if literal {
+            regex::escape(pattern)
+        } else {
+            pattern.to_string()
+        };
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(ignore_case)
+            .multi_line(true)
+            .build()?;
+        Ok(Self { regex })
+    }
+
+    /// Counts the matches in `text` and collects the 1-based numbers of the
+    /// lines they start on. Empty matches, such as those of `^` or `x*`
+    /// between two other characters, are skipped.
+    fn find(&self, text: &str) -> (usize, Vec<usize>) {
+        let mut hits = 0;
+        let mut lines: Vec<usize> = Vec::new();
+        let (mut line, mut scanned) = (1, 0);
+
+        for found in self.regex.find_iter(text).filter(|found| !found.is_empty()) {
+            hits += 1;
+            line += text[scanned..found.start()].matches('\n').count();
+            scanned = found.start();
+            if lines.last() != Some(&line) {
+                lines.push(line);
+            }
+        }
+
+        (hits, lines)
+    }
+}
+
+/// A file with at least one match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileMatch {
+    pub path: PathBuf,
+    /// Number of matches in the file
+    pub hits: usize,
+    /// 1-based numbers of the lines matches start on, in ascending order
+    pub lines: Vec<usize>,
+}
+
+impl FileMatch {
+    /// Returns the line ranges covering every matching line with `context`
+    /// lines before and after, merged where they overlap.
+    pub fn regions(&self, context: usize) -> Vec<LineRange> {
+        let ranges: Vec<LineRange> = self
+            .lines
+            .iter()
+            .map(|&line| LineRange::new(line.saturating_sub(context).max(1), Some(line + context)))
+            .collect();
+        line_range::merge(&ranges)
+    }
+}
+
+/// Searches the files under the workspace roots and returns those matching
+/// `query`, in path order.
+///
+/// Ignore rules are always respected; hidden files are only searched if
+/// `include_hidden` is set. Files that cannot be read or look binary are
+/// skipped.
+pub fn search(workspace: &Workspace, query: &Query, include_hidden: bool) -> Vec<FileMatch> {
+    let files = walk(workspace, include_hidden, |kind| kind.is_file());
+
+    let mut matches: Vec<FileMatch> = files
+        .into_par_iter()
+        .filter_map(|path| {
+            let bytes = fs::read(&path).ok()?;
+            let decoded = encoding::decode(&bytes, None).ok()?;
+            let (hits, lines) = query.find(&decoded.text);
+            (hits > 0).then_some(FileMatch { path, hits, lines })
+        })
+        .collect();
+
+    matches.sort_by(|a, b| {
+        (workspace.root_index(&a.path), &a.path).cmp(&(workspace.root_index(&b.path), &b.path))
+    });
+    matches
+}
+
+/// Returns the directories under the workspace roots that [`search`] looks
+/// in, including the roots themselves.
+pub fn directories(workspace: &Workspace, include_hidden: bool) -> Vec<PathBuf> {
+    walk(workspace, include_hidden, |kind| kind.is_dir())
+}
+
+/// Walks the workspace roots and returns the entries whose type passes `keep`.
+fn walk(
+    workspace: &Workspace,
+    include_hidden: bool,
+    keep: impl Fn(std::fs::FileType) -> bool,
+) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for root in workspace.roots() {
+        let walk = WalkBuilder::new(&root.path)
+            .hidden(!include_hidden)
+            .require_git(false)
+            .build();
+        paths.extend(
+            walk.filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_some_and(&keep))
+                .map(|entry| entry.into_path()),
+        );
+    }
+    paths
+}
@@ -1,4 +1,5 @@
-use anyhow::Result;
+use crate::error::{Result, TreeTxtError};
+use crate::project::Workspace;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -22,57 +23,129 @@ pub struct StateManager {
 
 impl StateManager {
     pub fn new(project_dir: &Path) -> Self {
-        let project_key = project_dir
-            .canonicalize()
-            .unwrap_or_else(|_| project_dir.to_path_buf())
-            .to_string_lossy()
-            .to_string();
-
-        let state_dir = if let Some(config_dir) = dirs::config_dir() {
-            config_dir.join("tree-txt")
-        } else {
-            PathBuf::from(".tree-txt")
-        };
+        Self {
+            project_key: project_key(project_dir),
+            state_file: state_file(),
+        }
+    }
 
-        let state_file = state_dir.join("state.toml");
+    /// Creates a state manager for a workspace.
+    ///
+    /// A single-root workspace shares its saved selections with
+    /// [`StateManager::new`]. A multi-root workspace stores the combined
+    /// selection under a key made of all its roots, independent of their order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tree_txt::StateManager;
+    /// use tree_txt::project::Workspace;
+    /// use std::path::PathBuf;
+    ///
+    /// let a = Workspace::new(vec![PathBuf::from("/code/client"), PathBuf::from("/code/server")]);
+    /// let b = Workspace::new(vec![PathBuf::from("/code/server"), PathBuf::from("/code/client")]);
+    /// assert_eq!(
+    ///     StateManager::for_workspace(&a).project_key(),
+    ///     StateManager::for_workspace(&b).project_key()
+    /// );
+    /// ```
+    pub fn for_workspace(workspace: &Workspace) -> Self {
+        if !workspace.is_multi_root() {
+            return Self::new(workspace.primary());
+        }
+
+        let mut keys: Vec<String> = workspace
+            .roots()
+            .iter()
+            .map(|root| project_key(&root.path))
+            .collect();
+        keys.sort();
 
         Self {
-            project_key,
-            state_file,
+            project_key: format!("workspace:{}", keys.join(";")),
+            state_file: state_file(),
         }
     }
 
     pub fn load_selections(&self) -> Result<Vec<PathBuf>> {
-        if !self.state_file.exists() {
-            return Ok(Vec::new());
+        Ok(self
+            .load_project_state()?
+            .map(|project_state| project_state.selected_files)
+            .unwrap_or_default())
+    }
+
+    /// Returns the key identifying this project in the state file.
+    pub fn project_key(&self) -> &str {
+        &self.project_key
+    }
+
+    /// Returns the path of the state file shared by all projects.
+    pub fn state_file(&self) -> &Path {
+        &self.state_file
+    }
+
+    /// Loads the saved state of this project, if any.
+    pub fn load_project_state(&self) -> Result<Option<ProjectState>> {
+        let mut global_state = self.load_global_state()?;
+        Ok(global_state.projects.remove(&self.project_key))
+    }
+
+    /// Lists every project with saved selections, sorted by project key.
+    pub fn list_projects(&self) -> Result<Vec<(String, ProjectState)>> {
+        let mut projects: Vec<_> = self.load_global_state()?.projects.into_iter().collect();
+        projects.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(projects)
+    }
+
+    /// Removes this project's saved selections. Returns `false` if there were none.
+    pub fn clear_selections(&mut self) -> Result<bool> {
+        let mut global_state = self.load_global_state()?;
+        if global_state.projects.remove(&self.project_key).is_none() {
+            return Ok(false);
         }
 
-        let content = fs::read_to_string(&self.state_file)?;
-        let global_state: GlobalState = toml::from_str(&content)?;
+        self.write_global_state(&global_state)?;
+        Ok(true)
+    }
 
-        if let Some(project_state) = global_state.projects.get(&self.project_key) {
-            Ok(project_state.selected_files.clone())
-        } else {
-            Ok(Vec::new())
+    fn load_global_state(&self) -> Result<GlobalState> {
+        if !self.state_file.exists() {
+            return Ok(GlobalState::default());
         }
+
+        let content = fs::read_to_string(&self.state_file)
+            .map_err(TreeTxtError::state_file(&self.state_file, "read"))?;
+        toml::from_str(&content).map_err(TreeTxtError::state_file(&self.state_file, "parse"))
     }
 
-    pub fn save_selections(&mut self, selections: &[PathBuf]) -> Result<()> {
+    fn write_global_state(&self, global_state: &GlobalState) -> Result<()> {
         if let Some(parent) = self.state_file.parent() {
-            fs::create_dir_all(parent)?;
+            fs::create_dir_all(parent).map_err(TreeTxtError::state_file(
+                &self.state_file,
+                "create directory for",
+            ))?;
         }
 
-        let mut global_state = if self.state_file.exists() {
-            let content = fs::read_to_string(&self.state_file)?;
-            toml::from_str(&content).unwrap_or_default()
-        } else {
-            GlobalState::default()
+        let content = toml::to_string_pretty(global_state)
+            .map_err(TreeTxtError::state_file(&self.state_file, "serialize"))?;
+        fs::write(&self.state_file, content)
+            .map_err(TreeTxtError::state_file(&self.state_file, "write"))
+    }
+
+    pub fn save_selections(&mut self, selections: &[PathBuf]) -> Result<()> {
+        // A corrupt state file is replaced rather than blocking new selections
+        let mut global_state = match self.load_global_state() {
+            Err(TreeTxtError::StateFile {
+                operation: "parse", ..
+            }) => GlobalState::default(),
+            other => other?,
         };
 
         let project_state = ProjectState {
             selected_files: selections.to_vec(),
             last_updated: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)?
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_err(TreeTxtError::state_file(&self.state_file, "timestamp"))?
                 .as_secs(),
         };
 
@@ -80,9 +153,24 @@ impl StateManager {
             .projects
             .insert(self.project_key.clone(), project_state);
 
-        let content = toml::to_string_pretty(&global_state)?;
-        fs::write(&self.state_file, content)?;
-
-        Ok(())
+        self.write_global_state(&global_state)
     }
 }
+
+fn project_key(project_dir: &Path) -> String {
+    project_dir
+        .canonicalize()
+        .unwrap_or_else(|_| project_dir.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+fn state_file() -> PathBuf {
+    let state_dir = if let Some(config_dir) = dirs::config_dir() {
+        config_dir.join("tree-txt")
+    } else {
+        PathBuf::from(".tree-txt")
+    };
+
+    state_dir.join("state.toml")
+}
@@ -0,0 +1,271 @@
+//! # Terminal Session
+//!
+//! Switches the terminal into the mode the interactive selector draws in
+//! (raw input, the alternate screen and mouse capture) and guarantees it is
+//! switched back however the selector ends:
+//!
+//! - when the session is finished or dropped, including on an early `?`
+//! - on a panic, from a panic hook that runs before the message is printed,
+//!   so the message lands on the normal screen
+//! - on SIGINT and SIGTERM, which are reported as [`Signal::Terminate`]
+//!   instead of killing the process with the terminal still in raw mode
+//!
+//! Once the session ends, these signals take their default action again, so
+//! Ctrl+C still stops the program afterwards (e.g. in `--watch` mode).
+//!
+//! On Unix, SIGTSTP (e.g. `kill -TSTP`, or Ctrl+Z forwarded by the selector)
+//! suspends the process with the terminal restored and sets it up again on
+//! resume.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use tree_txt::terminal::{Signal, TerminalSession};
+//!
+//! let mut session = TerminalSession::start()?;
+//! loop {
+//!     session.terminal().draw(|frame| {
+//!         frame.render_widget("Press Ctrl+C to stop", frame.area());
+//!     })?;
+//!     match session.pending_signal() {
+//!         Some(Signal::Terminate) => break,
+//!         Some(Signal::Suspend) => session.suspend()?,
+//!         None => std::thread::sleep(std::time::Duration::from_millis(100)),
+//!     }
+//! }
+//! session.finish()?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::error::{Result, TreeTxtError};
+use ratatui::crossterm::{
+    ExecutableCommand,
+    event::{DisableMouseCapture, EnableMouseCapture},
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use ratatui::prelude::{CrosstermBackend, Terminal};
+use signal_hook::SigId;
+use signal_hook::consts::{SIGINT, SIGTERM};
+use std::io::{Stdout, stdout};
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Once};
+
+/// Whether a session has the terminal set up, read by the panic hook.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+static PANIC_HOOK: Once = Once::new();
+
+/// Set while no session catches signals. The handlers installed by
+/// [`SIGNAL_DEFAULTS`] then run the default action of the signal.
+static SIGNALS_RELEASED: LazyLock<Arc<AtomicBool>> =
+    LazyLock::new(|| Arc::new(AtomicBool::new(false)));
+
+static SIGNAL_DEFAULTS: Once = Once::new();
+
+/// A signal received while the session is running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    /// SIGINT or SIGTERM: the program should stop
+    Terminate,
+    /// SIGTSTP: the program should [`suspend`](TerminalSession::suspend)
+    Suspend,
+}
+
+/// The terminal set up for the interactive selector. Dropping it restores
+/// the terminal.
+pub struct TerminalSession {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+    signals: SignalFlags,
+}
+
+impl TerminalSession {
+    /// Installs the panic hook and signal handlers, enables raw mode and
+    /// mouse capture and enters the alternate screen.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TreeTxtError::Terminal`] if the terminal cannot be set up.
+    /// Whatever was already set up is restored.
+    pub fn start() -> Result<Self> {
+        install_panic_hook();
+        // Catch signals first, so one arriving during setup still restores
+        let signals = SignalFlags::register()?;
+
+        enter()
+            .and_then(|()| {
+                Terminal::new(CrosstermBackend::new(stdout()))
+                    .map_err(TreeTxtError::terminal("initialize the terminal"))
+            })
+            .map(|terminal| Self { terminal, signals })
+            .inspect_err(|_| {
+                let _ = restore();
+            })
+    }
+
+    /// Returns the terminal to draw on.
+    pub fn terminal(&mut self) -> &mut Terminal<CrosstermBackend<Stdout>> {
+        &mut self.terminal
+    }
+
+    /// Returns the signal received since the last call, if any. A
+    /// [`Signal::Terminate`] is reported on every call once received.
+    pub fn pending_signal(&self) -> Option<Signal> {
+        if self.signals.terminate.load(Ordering::SeqCst) {
+            Some(Signal::Terminate)
+        } else if self.signals.suspend.swap(false, Ordering::SeqCst) {
+            Some(Signal::Suspend)
+        } else {
+            None
+        }
+    }
+
+    /// Restores the terminal and stops the process like the default SIGTSTP
+    /// handler would. Once the shell resumes it, sets the terminal up again
+    /// and clears it so the next draw repaints everything.
+    ///
+    /// Does nothing on platforms without job control.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TreeTxtError::Terminal`] if the terminal cannot be restored
+    /// or set up again.
+    pub fn suspend(&mut self) -> Result<()> {
+        #[cfg(unix)]
+        {
+            restore()?;
+            signal_hook::low_level::emulate_default_handler(signal_hook::consts::SIGTSTP)
+                .map_err(TreeTxtError::terminal("suspend"))?;
+            enter()?;
+            self.terminal
+                .clear()
+                .map_err(TreeTxtError::terminal("redraw the interface"))?;
+        }
+        Ok(())
+    }
+
+    /// Restores the terminal, reporting failures that [`Drop`] would ignore.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TreeTxtError::Terminal`] if a step of the restoration fails.
+    /// Every step is still attempted.
+    pub fn finish(self) -> Result<()> {
+        restore()
+    }
+}
+
+impl Drop for TerminalSession {
+    fn drop(&mut self) {
+        if ACTIVE.load(Ordering::SeqCst) {
+            let _ = restore();
+        }
+    }
+}
+
+/// Flags set by the signal handlers, which are removed again on drop.
+///
+/// Removing a handler does not reinstate the default action of its signal,
+/// which would leave the signal ignored. Every signal therefore also gets a
+/// handler that runs the default action while [`SIGNALS_RELEASED`] is set.
+struct SignalFlags {
+    terminate: Arc<AtomicBool>,
+    suspend: Arc<AtomicBool>,
+    handlers: Vec<SigId>,
+}
+
+impl SignalFlags {
+    fn register() -> Result<Self> {
+        let mut defaults = Ok(());
+        SIGNAL_DEFAULTS.call_once(|| {
+            defaults = SIGNALS.iter().try_for_each(|&signal| {
+                signal_hook::flag::register_conditional_default(
+                    signal,
+                    Arc::clone(&SIGNALS_RELEASED),
+                )
+                .map(drop)
+                .map_err(TreeTxtError::terminal("install signal handlers"))
+            });
+        });
+        defaults?;
+        SIGNALS_RELEASED.store(false, Ordering::SeqCst);
+
+        let mut flags = Self {
+            terminate: Arc::new(AtomicBool::new(false)),
+            suspend: Arc::new(AtomicBool::new(false)),
+            handlers: Vec::new(),
+        };
+
+        let register = |signal, flag: &Arc<AtomicBool>| {
+            signal_hook::flag::register(signal, Arc::clone(flag))
+                .map_err(TreeTxtError::terminal("install signal handlers"))
+        };
+        for signal in [SIGINT, SIGTERM] {
+            let handler = register(signal, &flags.terminate)?;
+            flags.handlers.push(handler);
+        }
+        #[cfg(unix)]
+        {
+            let handler = register(signal_hook::consts::SIGTSTP, &flags.suspend)?;
+            flags.handlers.push(handler);
+        }
+        Ok(flags)
+    }
+}
+
+impl Drop for SignalFlags {
+    fn drop(&mut self) {
+        for handler in self.handlers.drain(..) {
+            signal_hook::low_level::unregister(handler);
+        }
+        SIGNALS_RELEASED.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Signals caught while a session is running.
+#[cfg(unix)]
+const SIGNALS: [i32; 3] = [SIGINT, SIGTERM, signal_hook::consts::SIGTSTP];
+#[cfg(not(unix))]
+const SIGNALS: [i32; 2] = [SIGINT, SIGTERM];
+
+/// Restores the terminal before the default panic message is printed.
+fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if ACTIVE.load(Ordering::SeqCst) {
+                let _ = restore();
+            }
+            previous(info);
+        }));
+    });
+}
+
+/// Enables raw mode, enters the alternate screen and captures the mouse.
+fn enter() -> Result<()> {
+    ACTIVE.store(true, Ordering::SeqCst);
+    enable_raw_mode().map_err(TreeTxtError::terminal("enable raw mode"))?;
+    stdout()
+        .execute(EnterAlternateScreen)
+        .map_err(TreeTxtError::terminal("enter the alternate screen"))?;
+    stdout()
+        .execute(EnableMouseCapture)
+        .map_err(TreeTxtError::terminal("enable mouse capture"))?;
+    Ok(())
+}
+
+/// Releases the mouse, leaves the alternate screen and disables raw mode.
+/// Every step is attempted even if an earlier one fails.
+fn restore() -> Result<()> {
+    ACTIVE.store(false, Ordering::SeqCst);
+    let mouse = stdout()
+        .execute(DisableMouseCapture)
+        .map(|_| ())
+        .map_err(TreeTxtError::terminal("disable mouse capture"));
+    let screen = stdout()
+        .execute(LeaveAlternateScreen)
+        .map(|_| ())
+        .map_err(TreeTxtError::terminal("leave the alternate screen"));
+    let raw = disable_raw_mode().map_err(TreeTxtError::terminal("disable raw mode"));
+    raw.and(screen).and(mouse)
+}
@@ -0,0 +1,345 @@
+//! # Themes
+//!
+//! Colors and symbols of the interactive selector.
+//!
+//! A [`Theme`] holds one named style per part of the interface. Three themes
+//! are built in (`dark`, `light` and `high-contrast`), and every style can be
+//! overridden in the `[theme]` table of the user configuration (see
+//! [`user_config`](crate::user_config)). [`Symbols`] come in a Unicode set and
+//! an ASCII set for terminals and fonts that render emoji or box drawing
+//! characters poorly.
+//!
+//! When the `NO_COLOR` environment variable is set to a non-empty value, all
+//! colors are dropped and only bold, underline and reverse video are used
+//! (see <https://no-color.org>).
+//!
+//! # Examples
+//!
+//! ```rust
+//! use ratatui::style::{Color, Modifier};
+//! use tree_txt::theme::{Symbols, Theme};
+//!
+//! let theme = Theme::named("light").unwrap();
+//! assert_eq!(theme.directory.fg, Some(Color::Blue));
+//!
+//! let plain = theme.without_colors();
+//! assert_eq!(plain.directory.fg, None);
+//! assert!(plain.cursor.add_modifier.contains(Modifier::REVERSED));
+//! assert_eq!(plain.symbols, Symbols::UNICODE);
+//! ```
+
+use crate::error::{Result, TreeTxtError};
+use ratatui::style::{Color, Modifier, Style};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+
+/// Styles of the interactive selector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Directory rows
+    pub directory: Style,
+    /// Selected file rows
+    pub selected: Style,
+    /// Unselected file rows
+    pub unselected: Style,
+    /// The row under the cursor, drawn on top of the row's own style
+    pub cursor: Style,
+    /// The help bar and key names in the help overlay
+    pub help: Style,
+    /// Borders and titles of panels
+    pub border: Style,
+    /// Status messages and warnings
+    pub status: Style,
+    /// Characters used to draw the tree
+    pub symbols: Symbols,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// Names of the built-in themes.
+    pub const NAMES: [&'static str; 3] = ["dark", "light", "high-contrast"];
+
+    /// Style names accepted in the `[theme.styles]` table.
+    pub const STYLE_NAMES: [&'static str; 7] = [
+        "directory",
+        "selected",
+        "unselected",
+        "cursor",
+        "help",
+        "border",
+        "status",
+    ];
+
+    /// Returns the built-in theme called `name`.
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// Colors for terminals with a dark background (the default).
+    pub fn dark() -> Self {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        Self {
+            directory: bold.fg(Color::Cyan),
+            selected: bold.fg(Color::Green),
+            unselected: Style::default().fg(Color::White),
+            cursor: bold.bg(Color::DarkGray),
+            help: Style::default().fg(Color::Yellow),
+            border: Style::default(),
+            status: bold.fg(Color::Red),
+            symbols: Symbols::UNICODE,
+        }
+    }
+
+    /// Colors for terminals with a light background.
+    pub fn light() -> Self {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        Self {
+            directory: bold.fg(Color::Blue),
+            selected: bold.fg(Color::Green),
+            unselected: Style::default().fg(Color::Black),
+            cursor: bold.bg(Color::Gray),
+            help: Style::default().fg(Color::Magenta),
+            border: Style::default().fg(Color::DarkGray),
+            status: bold.fg(Color::Red),
+            symbols: Symbols::UNICODE,
+        }
+    }
+
+    /// Bright colors and reverse video for the cursor, for low-vision use
+    /// and poorly calibrated displays.
+    pub fn high_contrast() -> Self {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        Self {
+            directory: bold.fg(Color::LightCyan),
+            selected: bold
+                .fg(Color::LightGreen)
+                .add_modifier(Modifier::UNDERLINED),
+            unselected: Style::default().fg(Color::White),
+            cursor: bold.add_modifier(Modifier::REVERSED),
+            help: bold.fg(Color::LightYellow),
+            border: bold.fg(Color::White),
+            status: bold.fg(Color::LightRed),
+            symbols: Symbols::UNICODE,
+        }
+    }
+
+    /// Returns this theme with every color removed. The cursor switches to
+    /// reverse video so it stays visible.
+    pub fn without_colors(self) -> Self {
+        let plain = |style: Style| Style {
+            fg: None,
+            bg: None,
+            underline_color: None,
+            ..style
+        };
+        Self {
+            directory: plain(self.directory),
+            selected: plain(self.selected),
+            unselected: plain(self.unselected),
+            cursor: plain(self.cursor).add_modifier(Modifier::REVERSED),
+            help: plain(self.help),
+            border: plain(self.border),
+            status: plain(self.status),
+            symbols: self.symbols,
+        }
+    }
+
+    /// Returns the style called `name`, one of [`STYLE_NAMES`](Self::STYLE_NAMES).
+    fn style_mut(&mut self, name: &str) -> Option<&mut Style> {
+        match name {
+            "directory" => Some(&mut self.directory),
+            "selected" => Some(&mut self.selected),
+            "unselected" => Some(&mut self.unselected),
+            "cursor" => Some(&mut self.cursor),
+            "help" => Some(&mut self.help),
+            "border" => Some(&mut self.border),
+            "status" => Some(&mut self.status),
+            _ => None,
+        }
+    }
+}
+
+/// Returns `true` if the `NO_COLOR` environment variable asks for output
+/// without colors.
+pub fn no_color_requested() -> bool {
+    env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+/// Characters used to draw the file tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbols {
+    pub expanded: &'static str,
+    pub collapsed: &'static str,
+    /// Drawn before directory names
+    pub folder: &'static str,
+    pub checked: &'static str,
+    pub unchecked: &'static str,
+    /// Width of the arrow and checkbox markers, in columns
+    pub marker_width: u16,
+    /// Frames of the loading indicator, as wide as the arrows without their
+    /// trailing space
+    pub spinner: &'static [&'static str],
+    /// Suffix of files exported as outlines
+    pub outline: &'static str,
+    /// Suffix of files exported as matching lines only
+    pub regions: &'static str,
+    pub warning: &'static str,
+    /// Text cursor of the search prompt
+    pub prompt_cursor: &'static str,
+}
+
+impl Symbols {
+    /// Unicode arrows, check marks and emoji.
+    pub const UNICODE: Self = Self {
+        expanded: "▼ ",
+        collapsed: "▶ ",
+        folder: "📁 ",
+        checked: "✓ ",
+        unchecked: "  ",
+        marker_width: 2,
+        spinner: &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+        outline: " ◇ outline",
+        regions: " ⋯ matching lines",
+        warning: "⚠ ",
+        prompt_cursor: "▏",
+    };
+
+    /// Plain ASCII, for terminals and fonts that misrender the Unicode set.
+    pub const ASCII: Self = Self {
+        expanded: "[-] ",
+        collapsed: "[+] ",
+        folder: "",
+        checked: "[x] ",
+        unchecked: "[ ] ",
+        marker_width: 4,
+        spinner: &["[|]", "[/]", "[-]", "[\\]"],
+        outline: " (outline)",
+        regions: " (matching lines)",
+        warning: "! ",
+        prompt_cursor: "_",
+    };
+}
+
+/// The `[theme]` table of the user configuration.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThemeConfig {
+    /// Built-in theme to start from
+    #[serde(default = "default_theme_name")]
+    pub name: String,
+    /// Draw the tree with ASCII characters instead of Unicode symbols and emoji
+    #[serde(default)]
+    pub ascii: bool,
+    /// Per-style overrides, keyed by [`Theme::STYLE_NAMES`]
+    #[serde(default)]
+    pub styles: BTreeMap<String, StyleConfig>,
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        Self {
+            name: default_theme_name(),
+            ascii: false,
+            styles: BTreeMap::new(),
+        }
+    }
+}
+
+fn default_theme_name() -> String {
+    "dark".to_string()
+}
+
+/// A style override, e.g. `{ fg = "blue", bold = true }`.
+///
+/// Colors are names such as `blue` or `light-green`, `#rrggbb` or a palette
+/// index. Unset fields keep the value of the built-in theme.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StyleConfig {
+    pub fg: Option<String>,
+    pub bg: Option<String>,
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub underline: Option<bool>,
+    pub reversed: Option<bool>,
+}
+
+impl ThemeConfig {
+    /// Builds the configured theme, dropping colors if `no_color` is set
+    /// (see [`no_color_requested`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TreeTxtError::ConfigError`] for an unknown theme, style name
+    /// or color.
+    pub fn theme(&self, no_color: bool) -> Result<Theme> {
+        let mut theme = Theme::named(&self.name).ok_or_else(|| {
+            TreeTxtError::ConfigError(format!(
+                "Unknown theme '{}', expected one of: {}",
+                self.name,
+                Theme::NAMES.join(", ")
+            ))
+        })?;
+
+        for (name, config) in &self.styles {
+            let style = theme.style_mut(name).ok_or_else(|| {
+                TreeTxtError::ConfigError(format!(
+                    "Unknown style '{name}' in [theme.styles], expected one of: {}",
+                    Theme::STYLE_NAMES.join(", ")
+                ))
+            })?;
+            *style = config.apply(*style, name)?;
+        }
+
+        if self.ascii {
+            theme.symbols = Symbols::ASCII;
+        }
+
+        Ok(if no_color {
+            theme.without_colors()
+        } else {
+            theme
+        })
+    }
+}
+
+impl StyleConfig {
+    fn apply(&self, mut style: Style, name: &str) -> Result<Style> {
+        let color = |value: &str| -> Result<Color> {
+            value.parse().map_err(|_| {
+                TreeTxtError::ConfigError(format!("Invalid color '{value}' for style '{name}'"))
+            })
+        };
+
+        if let Some(fg) = &self.fg {
+            style.fg = Some(color(fg)?);
+        }
+        if let Some(bg) = &self.bg {
+            style.bg = Some(color(bg)?);
+        }
+        for (enabled, modifier) in [
+            (self.bold, Modifier::BOLD),
+            (self.italic, Modifier::ITALIC),
+            (self.underline, Modifier::UNDERLINED),
+            (self.reversed, Modifier::REVERSED),
+        ] {
+            style = match enabled {
+                Some(true) => style.add_modifier(modifier),
+                Some(false) => style.remove_modifier(modifier),
+                None => style,
+            };
+        }
+        Ok(style)
+    }
+}
@@ -0,0 +1,46 @@
+//! # Token Estimation
+//!
+//! Approximates how many LLM tokens a piece of text will use, so exports can
+//! be sized for chat tools without depending on a specific tokenizer.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use tree_txt::tokens::{SizeUnit, estimate_tokens, measure};
+//!
+//! assert_eq!(estimate_tokens("fn main() {}"), 3);
+//! assert_eq!(measure("fn main() {}", SizeUnit::Bytes), 12);
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Average number of characters per token for source code and English text
+/// with common BPE tokenizers.
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Unit used to measure the size of an export.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeUnit {
+    /// UTF-8 encoded bytes
+    #[default]
+    Bytes,
+    /// Estimated LLM tokens
+    Tokens,
+}
+
+/// Estimates the number of tokens in `text`.
+///
+/// This is a heuristic (roughly four characters per token), not an exact
+/// count for any particular model.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Measures `text` in the given unit.
+pub fn measure(text: &str, unit: SizeUnit) -> usize {
+    match unit {
+        SizeUnit::Bytes => text.len(),
+        SizeUnit::Tokens => estimate_tokens(text),
+    }
+}
@@ -0,0 +1,483 @@
+//! # Content Transforms
+//!
+//! Optional passes applied to file contents before they are exported, used to
+//! reduce the size of an export (and therefore its token count).
+//!
+//! The available passes are:
+//! - **Comment stripping** - removes line and block comments using
+//!   language-aware rules, leaving string literals untouched
+//! - **Blank-line collapsing** - reduces runs of blank lines to a single one
+//! - **Trailing-whitespace trimming** - removes whitespace at the end of lines
+//!
+//! A separate [`Normalization`] pass runs before everything else, including
+//! redaction, and can convert line endings to LF, expand tabs and strip a
+//! UTF-8 byte order mark.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use tree_txt::language::Language;
+//! use tree_txt::transform::{Transforms, apply};
+//!
+//! let source = "let url = \"http://example.com\"; // the endpoint\n\n\n\nfn main() {}\n";
+//! let transforms = Transforms {
+//!     strip_comments: true,
+//!     collapse_blank_lines: true,
+//!     trim_trailing_whitespace: true,
+//! };
+//!
+//! let result = apply(source, Language::Rust, &transforms);
+//! assert_eq!(result, "let url = \"http://example.com\";\n\nfn main() {}\n");
+//! ```
+
+use crate::language::{CommentSyntax, Language};
+use serde::{Deserialize, Serialize};
+
+/// The set of transform passes to apply to a file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Transforms {
+    /// Remove comments using the file's language rules
+    pub strip_comments: bool,
+    /// Collapse runs of blank lines into a single blank line
+    pub collapse_blank_lines: bool,
+    /// Remove trailing whitespace from every line
+    pub trim_trailing_whitespace: bool,
+}
+
+impl Transforms {
+    /// Returns `true` if at least one pass is enabled.
+    pub fn any(&self) -> bool {
+        self.strip_comments || self.collapse_blank_lines || self.trim_trailing_whitespace
+    }
+
+    /// Returns the names of the enabled passes, in the order they are applied.
+    pub fn names(&self) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        if self.strip_comments {
+            names.push("strip comments");
+        }
+        if self.trim_trailing_whitespace {
+            names.push("trim trailing whitespace");
+        }
+        if self.collapse_blank_lines {
+            names.push("collapse blank lines");
+        }
+        names
+    }
+}
+
+/// Whitespace normalization applied to file contents as soon as they are read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Normalization {
+    /// Convert CRLF and lone CR line endings to LF
+    pub line_endings: bool,
+    /// Expand tabs to spaces, with tab stops every this many columns
+    pub tab_width: Option<usize>,
+    /// Remove a leading UTF-8 byte order mark
+    pub strip_bom: bool,
+}
+
+impl Normalization {
+    /// Returns `true` if at least one normalization is enabled.
+    pub fn any(&self) -> bool {
+        self.line_endings || self.tab_width.is_some() || self.strip_bom
+    }
+
+    /// Returns the names of the enabled normalizations.
+    pub fn names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        if self.strip_bom {
+            names.push("strip BOM".to_string());
+        }
+        if self.line_endings {
+            names.push("LF line endings".to_string());
+        }
+        if let Some(width) = self.tab_width {
+            names.push(format!("tabs to {width} spaces"));
+        }
+        names
+    }
+}
+
+/// Applies the enabled normalizations to `content`.
+///
+/// # Examples
+///
+/// ```rust
+/// use tree_txt::transform::{Normalization, normalize};
+///
+/// let normalization = Normalization {
+///     line_endings: true,
+///     tab_width: Some(4),
+///     strip_bom: true,
+/// };
+/// assert_eq!(
+///     normalize("\u{feff}fn main() {\r\n\tlet x\t= 1;\r\n}", &normalization),
+///     "fn main() {\n    let x   = 1;\n}"
+/// );
+/// ```
+pub fn normalize(content: &str, normalization: &Normalization) -> String {
+    let mut result = content;
+    if normalization.strip_bom {
+        result = result.strip_prefix('\u{feff}').unwrap_or(result);
+    }
+
+    let mut result = if normalization.line_endings {
+        normalize_line_endings(result)
+    } else {
+        result.to_string()
+    };
+    if let Some(width) = normalization.tab_width {
+        result = expand_tabs(&result, width);
+    }
+
+    result
+}
+
+/// Converts CRLF and lone CR line endings to LF.
+pub fn normalize_line_endings(content: &str) -> String {
+    content.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// Replaces tabs with spaces up to the next multiple of `width` columns.
+///
+/// A width of 0 removes tabs.
+pub fn expand_tabs(content: &str, width: usize) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut column = 0;
+
+    for c in content.chars() {
+        match c {
+            '\t' => {
+                let spaces = if width == 0 {
+                    0
+                } else {
+                    width - column % width
+                };
+                result.extend(std::iter::repeat_n(' ', spaces));
+                column += spaces;
+            }
+            '\n' | '\r' => {
+                result.push(c);
+                column = 0;
+            }
+            _ => {
+                result.push(c);
+                column += 1;
+            }
+        }
+    }
+
+    result
+}
+
+/// Per-extension override of the global transform settings.
+///
+/// Unset fields fall back to the values in [`OutputFormat`](crate::OutputFormat).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransformOverride {
+    #[serde(default)]
+    pub strip_comments: Option<bool>,
+    #[serde(default)]
+    pub collapse_blank_lines: Option<bool>,
+    #[serde(default)]
+    pub trim_trailing_whitespace: Option<bool>,
+}
+
+impl TransformOverride {
+    /// Applies this override on top of `base`.
+    pub fn apply_to(&self, base: Transforms) -> Transforms {
+        Transforms {
+            strip_comments: self.strip_comments.unwrap_or(base.strip_comments),
+            collapse_blank_lines: self
+                .collapse_blank_lines
+                .unwrap_or(base.collapse_blank_lines),
+            trim_trailing_whitespace: self
+                .trim_trailing_whitespace
+                .unwrap_or(base.trim_trailing_whitespace),
+        }
+    }
+}
+
+/// Applies the enabled transform passes to `content`.
+///
+/// Comment stripping is skipped for languages without a known comment syntax.
+pub fn apply(content: &str, language: Language, transforms: &Transforms) -> String {
+    let mut result = content.to_string();
+
+    if transforms.strip_comments
+        && let Some(syntax) = language.comment_syntax()
+    {
+        result = strip_comments(&result, &syntax);
+    }
+    if transforms.trim_trailing_whitespace {
+        result = trim_trailing_whitespace(&result);
+    }
+    if transforms.collapse_blank_lines {
+        result = collapse_blank_lines(&result);
+    }
+
+    result
+}
+
+/// Removes comments from `source` according to `syntax`.
+///
+/// Lines that consisted only of a comment are removed entirely; lines with a
+/// trailing comment keep their code with the trailing whitespace trimmed.
+///
+/// # Examples
+///
+/// ```rust
+/// use tree_txt::language::Language;
+/// use tree_txt::transform::strip_comments;
+///
+/// let source = "let s = r\"a\nb // not a comment\nc\"; // x\nlet y = 1; // c\n\nfn last() {}\n";
+/// let syntax = Language::Rust.comment_syntax().unwrap();
+/// assert_eq!(
+///     strip_comments(source, &syntax),
+///     "let s = r\"a\nb // not a comment\nc\";\nlet y = 1;\n\nfn last() {}\n"
+/// );
+/// ```
+pub fn strip_comments(source: &str, syntax: &CommentSyntax) -> String {
+    let mut stripped = String::with_capacity(source.len());
+    // One entry per output line: whether a comment was removed from it
+    let mut had_comment = vec![false];
+    let mut pos = 0;
+
+    while pos < source.len() {
+        let rest = &source[pos..];
+
+        if let Some((open, close)) = syntax.block.iter().find(|(open, _)| rest.starts_with(open)) {
+            let end = block_comment_end(source, pos, open, close, syntax.nested_blocks);
+            // Keep the newlines so line structure is preserved
+            for _ in source[pos..end].matches('\n') {
+                stripped.push('\n');
+                *had_comment.last_mut().unwrap() = true;
+                had_comment.push(true);
+            }
+            *had_comment.last_mut().unwrap() = true;
+            pos = end;
+            continue;
+        }
+
+        if syntax.line.iter().any(|marker| rest.starts_with(marker))
+            && (!syntax.line_marker_needs_space || preceded_by_space(source, pos))
+        {
+            *had_comment.last_mut().unwrap() = true;
+            pos += rest.find('\n').unwrap_or(rest.len());
+            continue;
+        }
+
+        if syntax.rust_literals
+            && let Some(len) = rust_literal_len(source, pos)
+        {
+            push_literal(&mut stripped, &mut had_comment, &rest[..len]);
+            pos += len;
+            continue;
+        }
+
+        if let Some(delimiter) = syntax
+            .strings
+            .iter()
+            .find(|delimiter| rest.starts_with(**delimiter))
+        {
+            let len = string_literal_len(rest, delimiter);
+            push_literal(&mut stripped, &mut had_comment, &rest[..len]);
+            pos += len;
+            continue;
+        }
+
+        let ch = rest.chars().next().unwrap_or_default();
+        stripped.push(ch);
+        if ch == '\n' {
+            had_comment.push(false);
+        }
+        pos += ch.len_utf8();
+    }
+
+    let mut result = String::with_capacity(stripped.len());
+    for (line, had_comment) in stripped.split_inclusive('\n').zip(had_comment) {
+        if !had_comment {
+            result.push_str(line);
+            continue;
+        }
+
+        let body = line.trim_end();
+        if body.is_empty() {
+            // The line held nothing but a comment
+            continue;
+        }
+        result.push_str(body);
+        result.push_str(line_ending(line));
+    }
+
+    result
+}
+
+/// Copies a string literal, starting a new output line for every newline
+/// inside it.
+fn push_literal(stripped: &mut String, had_comment: &mut Vec<bool>, literal: &str) {
+    stripped.push_str(literal);
+    had_comment.extend(literal.matches('\n').map(|_| false));
+}
+
+/// Removes trailing whitespace from every line, preserving line endings.
+pub fn trim_trailing_whitespace(content: &str) -> String {
+    content
+        .split_inclusive('\n')
+        .map(|line| format!("{}{}", line.trim_end(), line_ending(line)))
+        .collect()
+}
+
+/// Collapses runs of blank lines into a single blank line and drops leading
+/// blank lines.
+pub fn collapse_blank_lines(content: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut previous_blank = true;
+
+    for line in content.split_inclusive('\n') {
+        let blank = line.trim().is_empty();
+        if blank && previous_blank {
+            continue;
+        }
+        result.push_str(line);
+        previous_blank = blank;
+    }
+
+    result
+}
+
+/// Returns the length of the comment, string or char literal starting at
+/// `pos`, or `None` if `pos` is in plain code.
+///
+/// Lets other passes (such as outlining) scan code without being confused by
+/// braces inside strings or comments.
+pub(crate) fn literal_or_comment_len(
+    source: &str,
+    pos: usize,
+    syntax: &CommentSyntax,
+) -> Option<usize> {
+    let rest = &source[pos..];
+
+    if let Some((open, close)) = syntax.block.iter().find(|(open, _)| rest.starts_with(open)) {
+        return Some(block_comment_end(source, pos, open, close, syntax.nested_blocks) - pos);
+    }
+    if syntax.line.iter().any(|marker| rest.starts_with(marker))
+        && (!syntax.line_marker_needs_space || preceded_by_space(source, pos))
+    {
+        return Some(rest.find('\n').unwrap_or(rest.len()));
+    }
+    if syntax.rust_literals
+        && let Some(len) = rust_literal_len(source, pos)
+    {
+        return Some(len);
+    }
+    syntax
+        .strings
+        .iter()
+        .find(|delimiter| rest.starts_with(**delimiter))
+        .map(|delimiter| string_literal_len(rest, delimiter))
+}
+
+fn line_ending(line: &str) -> &'static str {
+    if line.ends_with("\r\n") {
+        "\r\n"
+    } else if line.ends_with('\n') {
+        "\n"
+    } else {
+        ""
+    }
+}
+
+fn preceded_by_space(source: &str, pos: usize) -> bool {
+    source[..pos]
+        .chars()
+        .next_back()
+        .is_none_or(char::is_whitespace)
+}
+
+fn block_comment_end(source: &str, start: usize, open: &str, close: &str, nested: bool) -> usize {
+    let mut depth = 0;
+    let mut pos = start;
+
+    while pos < source.len() {
+        let rest = &source[pos..];
+        if (nested && rest.starts_with(open)) || (depth == 0 && pos == start) {
+            depth += 1;
+            pos += open.len();
+        } else if rest.starts_with(close) {
+            depth -= 1;
+            pos += close.len();
+            if depth == 0 {
+                return pos;
+            }
+        } else {
+            pos += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+
+    source.len()
+}
+
+fn string_literal_len(rest: &str, delimiter: &str) -> usize {
+    let mut pos = delimiter.len();
+
+    while pos < rest.len() {
+        let tail = &rest[pos..];
+        if tail.starts_with(delimiter) {
+            return pos + delimiter.len();
+        }
+        if tail.starts_with('\\') {
+            pos += 1;
+        }
+        pos += rest[pos..].chars().next().map_or(1, char::len_utf8);
+    }
+
+    rest.len()
+}
+
+/// Returns the length of a Rust char literal, lifetime marker or raw string
+/// starting at `pos`, if there is one.
+fn rust_literal_len(source: &str, pos: usize) -> Option<usize> {
+    let rest = &source[pos..];
+    let previous = source[..pos].chars().next_back();
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+
+    if rest.starts_with('\'') {
+        let mut chars = rest.char_indices().skip(1);
+        let (_, first) = chars.next()?;
+        if first == '\\' {
+            // Escaped char literal such as '\n' or '\u{1F600}'
+            let close = rest.get(3..)?.find('\'')?;
+            return (close <= 8).then_some(close + 4);
+        }
+        if let Some((index, '\'')) = chars.next() {
+            return Some(index + 1);
+        }
+        // A lifetime or label: just the quote itself
+        return Some(1);
+    }
+
+    let raw_start = if rest.starts_with("br") {
+        2
+    } else if rest.starts_with('r') {
+        1
+    } else {
+        return None;
+    };
+    if previous.is_some_and(is_ident) {
+        return None;
+    }
+
+    let hashes = rest[raw_start..].chars().take_while(|&c| c == '#').count();
+    if !rest[raw_start + hashes..].starts_with('"') {
+        return None;
+    }
+
+    let body_start = raw_start + hashes + 1;
+    let terminator = format!("\"{}", "#".repeat(hashes));
+    let end = rest[body_start..]
+        .find(&terminator)
+        .map_or(rest.len(), |offset| body_start + offset + terminator.len());
+    Some(end)
+}
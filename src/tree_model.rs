@@ -0,0 +1,328 @@
+//! # Directory Tree Model
+//!
+//! An in-memory cache of directory listings for the interactive selector.
+//!
+//! Each directory is read from disk once, on a background thread, so the
+//! interface keeps responding while large directories load. Recursive walks
+//! (used when a whole directory is selected) also run in the background and
+//! fill the cache as they go. Results are collected with [`TreeModel::poll`]
+//! or [`TreeModel::wait`].
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use tree_txt::tree_model::{TreeModel, Update};
+//!
+//! let dir = std::env::temp_dir().join(format!("tree-txt-model-doc-{}", std::process::id()));
+//! std::fs::create_dir_all(dir.join("src"))?;
+//! std::fs::write(dir.join("src/main.rs"), "fn main() {}\n")?;
+//! std::fs::write(dir.join("Cargo.toml"), "")?;
+//!
+//! let mut model = TreeModel::new();
+//! model.request(&dir);
+//! while model.children(&dir).is_none() {
+//!     model.wait(Duration::from_millis(100));
+//! }
+//!
+//! let names: Vec<&str> = model.children(&dir).unwrap().iter().map(|e| e.name.as_str()).collect();
+//! assert_eq!(names, ["src", "Cargo.toml"]);
+//!
+//! model.walk(&dir, false);
+//! let files = loop {
+//!     if let Some(Update::Walked { files, .. }) = model
+//!         .wait(Duration::from_millis(100))
+//!         .into_iter()
+//!         .find(|update| matches!(update, Update::Walked { .. }))
+//!     {
+//!         break files;
+//!     }
+//! };
+//! assert_eq!(files, vec![dir.join("Cargo.toml"), dir.join("src/main.rs")]);
+//!
+//! // A walk requested again while it runs is reported once per request
+//! model.walk(&dir, false);
+//! model.walk(&dir, false);
+//! let mut walked = 0;
+//! while model.is_busy() {
+//!     walked += model
+//!         .wait(Duration::from_millis(100))
+//!         .iter()
+//!         .filter(|update| matches!(update, Update::Walked { .. }))
+//!         .count();
+//! }
+//! assert_eq!(walked, 2);
+//!
+//! std::fs::remove_dir_all(&dir)?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A file or directory inside a listed directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub path: PathBuf,
+    pub name: String,
+    pub is_dir: bool,
+    /// Whether the name starts with a dot
+    pub is_hidden: bool,
+}
+
+/// A change reported by [`TreeModel::poll`] or [`TreeModel::wait`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Update {
+    /// The listing of a directory is now available
+    Listed(PathBuf),
+    /// A directory could not be read
+    Failed { dir: PathBuf, message: String },
+    /// A recursive walk started with [`TreeModel::walk`] finished
+    Walked { dir: PathBuf, files: Vec<PathBuf> },
+}
+
+#[derive(Debug)]
+enum Listing {
+    Loading,
+    Loaded(Vec<Entry>),
+    Failed(String),
+}
+
+#[derive(Debug)]
+enum Message {
+    Listed {
+        dir: PathBuf,
+        result: std::result::Result<Vec<Entry>, String>,
+    },
+    Walked {
+        dir: PathBuf,
+        files: Vec<PathBuf>,
+    },
+}
+
+/// Cache of directory listings, loaded lazily on background threads.
+#[derive(Debug)]
+pub struct TreeModel {
+    listings: HashMap<PathBuf, Listing>,
+    /// Directories being walked, with the number of times each walk was requested
+    walks: HashMap<PathBuf, usize>,
+    /// Incremented by [`clear`](Self::clear) so results of older loads are dropped
+    generation: u64,
+    sender: Sender<(u64, Message)>,
+    receiver: Receiver<(u64, Message)>,
+}
+
+impl Default for TreeModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TreeModel {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            listings: HashMap::new(),
+            walks: HashMap::new(),
+            generation: 0,
+            sender,
+            receiver,
+        }
+    }
+
+    /// Returns the cached entries of `dir`, directories first, or `None` if it
+    /// has not been loaded (yet).
+    pub fn children(&self, dir: &Path) -> Option<&[Entry]> {
+        match self.listings.get(dir) {
+            Some(Listing::Loaded(entries)) => Some(entries),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` while `dir` is being loaded.
+    pub fn is_loading(&self, dir: &Path) -> bool {
+        matches!(self.listings.get(dir), Some(Listing::Loading))
+    }
+
+    /// Returns the error that prevented `dir` from being read, if any.
+    pub fn error(&self, dir: &Path) -> Option<&str> {
+        match self.listings.get(dir) {
+            Some(Listing::Failed(message)) => Some(message),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` while any load or walk is in progress.
+    pub fn is_busy(&self) -> bool {
+        !self.walks.is_empty()
+            || self
+                .listings
+                .values()
+                .any(|listing| matches!(listing, Listing::Loading))
+    }
+
+    /// Returns `true` while a walk of `dir` is in progress.
+    pub fn is_walking(&self, dir: &Path) -> bool {
+        self.walks.contains_key(dir)
+    }
+
+    /// Starts loading `dir` in the background unless it is cached or loading.
+    pub fn request(&mut self, dir: &Path) {
+        if self.listings.contains_key(dir) {
+            return;
+        }
+        self.listings.insert(dir.to_path_buf(), Listing::Loading);
+
+        let sender = self.sender.clone();
+        let generation = self.generation;
+        let dir = dir.to_path_buf();
+        thread::spawn(move || {
+            let result = read_entries(&dir);
+            let _ = sender.send((generation, Message::Listed { dir, result }));
+        });
+    }
+
+    /// Starts collecting every file below `dir` in the background. Hidden
+    /// files and directories are skipped unless `include_hidden` is set, and
+    /// symbolic links to directories are not followed.
+    ///
+    /// Every directory read along the way is added to the cache. Calling this
+    /// again while `dir` is being walked does not start another walk, but its
+    /// [`Update::Walked`] is reported once per call, so no request is lost.
+    pub fn walk(&mut self, dir: &Path, include_hidden: bool) {
+        let requests = self.walks.entry(dir.to_path_buf()).or_default();
+        *requests += 1;
+        if *requests > 1 {
+            return;
+        }
+
+        let sender = self.sender.clone();
+        let generation = self.generation;
+        let dir = dir.to_path_buf();
+        thread::spawn(move || {
+            let mut files = Vec::new();
+            let mut pending = vec![dir.clone()];
+            while let Some(current) = pending.pop() {
+                let result = read_entries(&current);
+                if let Ok(entries) = &result {
+                    for entry in entries.iter().filter(|e| include_hidden || !e.is_hidden) {
+                        let is_link = fs::symlink_metadata(&entry.path)
+                            .is_ok_and(|metadata| metadata.file_type().is_symlink());
+                        if !entry.is_dir {
+                            files.push(entry.path.clone());
+                        } else if !is_link {
+                            pending.push(entry.path.clone());
+                        }
+                    }
+                }
+                let message = Message::Listed {
+                    dir: current,
+                    result,
+                };
+                if sender.send((generation, message)).is_err() {
+                    return;
+                }
+            }
+
+            files.sort();
+            let _ = sender.send((generation, Message::Walked { dir, files }));
+        });
+    }
+
+    /// Forgets every cached listing, so directories are read again when next
+    /// requested. Loads and walks still in progress are abandoned.
+    pub fn clear(&mut self) {
+        self.listings.clear();
+        self.walks.clear();
+        self.generation += 1;
+    }
+
+    /// Applies finished background work to the cache without blocking.
+    pub fn poll(&mut self) -> Vec<Update> {
+        let mut updates = Vec::new();
+        while let Ok(message) = self.receiver.try_recv() {
+            self.apply(message, &mut updates);
+        }
+        updates
+    }
+
+    /// Like [`poll`](Self::poll), but waits up to `timeout` for background
+    /// work to finish if nothing is ready yet.
+    pub fn wait(&mut self, timeout: Duration) -> Vec<Update> {
+        let deadline = Instant::now() + timeout;
+        let mut updates = self.poll();
+        while updates.is_empty() && self.is_busy() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.receiver.recv_timeout(remaining) {
+                Ok(message) => {
+                    self.apply(message, &mut updates);
+                    updates.extend(self.poll());
+                }
+                Err(_) => break,
+            }
+        }
+        updates
+    }
+
+    fn apply(&mut self, (generation, message): (u64, Message), updates: &mut Vec<Update>) {
+        if generation != self.generation {
+            return;
+        }
+
+        match message {
+            Message::Listed { dir, result } => {
+                // Walks re-read directories that may already be cached
+                if matches!(self.listings.get(&dir), Some(Listing::Loaded(_))) {
+                    return;
+                }
+                match result {
+                    Ok(entries) => {
+                        self.listings.insert(dir.clone(), Listing::Loaded(entries));
+                        updates.push(Update::Listed(dir));
+                    }
+                    Err(message) => {
+                        self.listings
+                            .insert(dir.clone(), Listing::Failed(message.clone()));
+                        updates.push(Update::Failed { dir, message });
+                    }
+                }
+            }
+            Message::Walked { dir, files } => {
+                let requests = self.walks.remove(&dir).unwrap_or(1);
+                for _ in 1..requests {
+                    updates.push(Update::Walked {
+                        dir: dir.clone(),
+                        files: files.clone(),
+                    });
+                }
+                updates.push(Update::Walked { dir, files });
+            }
+        }
+    }
+}
+
+/// Reads the entries of `dir`, directories first, then files, both by name.
+fn read_entries(dir: &Path) -> std::result::Result<Vec<Entry>, String> {
+    let entries = fs::read_dir(dir).map_err(|err| err.to_string())?;
+
+    let mut entries: Vec<Entry> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            Entry {
+                is_dir: path.is_dir(),
+                is_hidden: name.starts_with('.'),
+                path,
+                name,
+            }
+        })
+        .collect();
+
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    Ok(entries)
+}
@@ -0,0 +1,168 @@
+//! # User Configuration
+//!
+//! Personal settings of the interactive selector, shared by all projects and
+//! read from `tree-txt/config.toml` in the user's configuration directory
+//! (e.g. `~/.config/tree-txt/config.toml` on Linux). The file is optional.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use ratatui::style::{Color, Modifier};
+//! use tree_txt::keymap::{Action, Preset};
+//! use tree_txt::theme::Symbols;
+//! use tree_txt::user_config::UserConfig;
+//!
+//! let config: UserConfig = toml::from_str(r##"
+//!     [keys]
+//!     preset = "emacs"
+//!     select_all = ["ctrl+a", "A"]
+//!     reload = "ctrl+r"
+//!
+//!     [theme]
+//!     name = "light"
+//!     ascii = true
+//!
+//!     [theme.styles]
+//!     directory = { fg = "#005f87", underline = true }
+//!     cursor = { bg = "yellow", bold = false }
+//! "##)?;
+//!
+//! assert_eq!(config.keys.preset, Preset::Emacs);
+//! let keymap = config.keymap()?;
+//! assert_eq!(keymap.keys_label(Action::SelectAll), "Ctrl+A/A");
+//! assert_eq!(keymap.keys_label(Action::Reload), "Ctrl+R");
+//!
+//! let theme = config.theme.theme(false)?;
+//! assert_eq!(theme.directory.fg, Some(Color::Rgb(0x00, 0x5f, 0x87)));
+//! assert!(theme.directory.add_modifier.contains(Modifier::UNDERLINED | Modifier::BOLD));
+//! assert_eq!(theme.cursor.bg, Some(Color::Yellow));
+//! assert!(!theme.cursor.add_modifier.contains(Modifier::BOLD));
+//! assert_eq!(theme.symbols, Symbols::ASCII);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::error::{Result, TreeTxtError};
+use crate::keymap::{Keymap, Preset};
+use crate::theme::{self, Theme, ThemeConfig};
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Settings from the user configuration file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserConfig {
+    /// Key bindings of the interactive selector
+    #[serde(default)]
+    pub keys: KeysConfig,
+    /// Colors and symbols of the interactive selector
+    #[serde(default)]
+    pub theme: ThemeConfig,
+}
+
+/// The `[keys]` table: a preset and per-action overrides.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct KeysConfig {
+    #[serde(default, deserialize_with = "deserialize_preset")]
+    pub preset: Preset,
+    /// Action names mapped to their keys
+    #[serde(flatten, deserialize_with = "deserialize_bindings")]
+    pub bindings: BTreeMap<String, Vec<String>>,
+}
+
+impl UserConfig {
+    /// Returns the path of the user configuration file.
+    pub fn path() -> PathBuf {
+        let config_dir = if let Some(config_dir) = dirs::config_dir() {
+            config_dir.join("tree-txt")
+        } else {
+            PathBuf::from(".tree-txt")
+        };
+
+        config_dir.join("config.toml")
+    }
+
+    /// Reads the user configuration file, or returns the defaults if there
+    /// is none.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TreeTxtError::ConfigError`] if the file exists but cannot be
+    /// read or parsed.
+    pub fn load() -> Result<Self> {
+        let path = Self::path();
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => {
+                return Err(TreeTxtError::ConfigError(format!(
+                    "Cannot read user config '{}': {err}",
+                    path.display()
+                )));
+            }
+        };
+
+        toml::from_str(&content).map_err(|err| {
+            TreeTxtError::ConfigError(format!("Invalid user config '{}': {err}", path.display()))
+        })
+    }
+
+    /// Builds the key bindings described by the `[keys]` table.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TreeTxtError::ConfigError`] for unknown actions or keys and
+    /// for keys bound to several actions.
+    pub fn keymap(&self) -> Result<Keymap> {
+        Keymap::new(self.keys.preset, &self.keys.bindings)
+    }
+
+    /// Builds the theme described by the `[theme]` table, without colors if
+    /// `NO_COLOR` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TreeTxtError::ConfigError`] for unknown themes, styles or
+    /// colors.
+    pub fn theme(&self) -> Result<Theme> {
+        self.theme.theme(theme::no_color_requested())
+    }
+}
+
+fn deserialize_preset<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Preset, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    Preset::from_name(&name).ok_or_else(|| {
+        serde::de::Error::custom(format!(
+            "unknown preset '{name}', expected one of: {}",
+            Preset::NAMES.join(", ")
+        ))
+    })
+}
+
+/// Accepts a single key or a list of keys for every action.
+fn deserialize_bindings<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<BTreeMap<String, Vec<String>>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Keys {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    let bindings = BTreeMap::<String, Keys>::deserialize(deserializer)?;
+    Ok(bindings
+        .into_iter()
+        .map(|(action, keys)| {
+            let keys = match keys {
+                Keys::One(key) => vec![key],
+                Keys::Many(keys) => keys,
+            };
+            (action, keys)
+        })
+        .collect())
+}
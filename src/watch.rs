@@ -0,0 +1,283 @@
+//! # Watch Mode
+//!
+//! Detects changes to the files of an export so it can be regenerated while
+//! you work.
+//!
+//! The watcher polls file modification times and sizes instead of relying on
+//! platform notification APIs, which keeps it dependency-free and reliable on
+//! network filesystems and in containers. Bursts of changes (such as an editor
+//! writing a backup file, then the file itself) are debounced into a single
+//! [`Changes`] set.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use tree_txt::watch::Watcher;
+//!
+//! let dir = std::env::temp_dir().join(format!("tree-txt-watch-doc-{}", std::process::id()));
+//! std::fs::create_dir_all(&dir)?;
+//! let file = dir.join("main.rs");
+//! std::fs::write(&file, "fn main() {}\n")?;
+//!
+//! let mut watcher = Watcher::new(&[file.clone()]).watch_dirs([dir.clone()]);
+//! assert!(watcher.poll().is_empty());
+//!
+//! std::fs::write(&file, "fn main() { println!(\"hi\"); }\n")?;
+//! std::fs::write(dir.join("lib.rs"), "")?;
+//!
+//! let changes = watcher
+//!     .debounce(Duration::from_millis(50))
+//!     .poll_interval(Duration::from_millis(10))
+//!     .wait_for_changes();
+//! assert_eq!(changes.modified, vec![file.clone()]);
+//! assert_eq!(changes.added, vec![dir.join("lib.rs")]);
+//! assert_eq!(changes.summary(&dir), "1 modified (main.rs), 1 added (lib.rs)");
+//!
+//! std::fs::remove_dir_all(&dir)?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Default time to wait for changes to settle before reporting them.
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Default interval between two scans of the watched paths.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Maximum number of file names listed per category in a summary.
+const SUMMARY_NAMES: usize = 3;
+
+/// Observable state of a file, used to detect modifications.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+type Snapshot = BTreeMap<PathBuf, FileStamp>;
+
+/// Files that changed between two scans.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Changes {
+    /// Files whose contents (modification time or size) changed
+    pub modified: Vec<PathBuf>,
+    /// Files that appeared
+    pub added: Vec<PathBuf>,
+    /// Files that disappeared
+    pub removed: Vec<PathBuf>,
+}
+
+impl Changes {
+    /// Returns `true` if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.modified.is_empty() && self.added.is_empty() && self.removed.is_empty()
+    }
+
+    /// Formats the changes as a single line, with paths relative to `base`.
+    pub fn summary(&self, base: &Path) -> String {
+        self.summary_with(|path| path.strip_prefix(base).unwrap_or(path).to_path_buf())
+    }
+
+    /// Formats the changes as a single line, showing each path as returned by `display`.
+    pub fn summary_with(&self, display: impl Fn(&Path) -> PathBuf) -> String {
+        let groups = [
+            ("modified", &self.modified),
+            ("added", &self.added),
+            ("removed", &self.removed),
+        ];
+
+        let parts: Vec<String> = groups
+            .into_iter()
+            .filter(|(_, paths)| !paths.is_empty())
+            .map(|(label, paths)| {
+                let mut names: Vec<String> = paths
+                    .iter()
+                    .take(SUMMARY_NAMES)
+                    .map(|path| display(path).display().to_string())
+                    .collect();
+                if paths.len() > SUMMARY_NAMES {
+                    names.push(format!("+{} more", paths.len() - SUMMARY_NAMES));
+                }
+                format!("{} {label} ({})", paths.len(), names.join(", "))
+            })
+            .collect();
+
+        if parts.is_empty() {
+            "no changes".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// Polls a set of files, and optionally directories, for changes.
+#[derive(Debug, Clone)]
+pub struct Watcher {
+    files: Vec<PathBuf>,
+    dirs: Vec<PathBuf>,
+    ignored: HashSet<PathBuf>,
+    snapshot: Snapshot,
+    debounce: Duration,
+    poll_interval: Duration,
+}
+
+impl Watcher {
+    /// Creates a watcher for `files`, taking their current state as the baseline.
+    pub fn new(files: &[PathBuf]) -> Self {
+        let mut watcher = Self {
+            files: files.to_vec(),
+            dirs: Vec::new(),
+            ignored: HashSet::new(),
+            snapshot: Snapshot::new(),
+            debounce: DEFAULT_DEBOUNCE,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        };
+        watcher.snapshot = watcher.scan();
+        watcher
+    }
+
+    /// Also watches the files and subdirectories directly inside `dirs`, so
+    /// new and deleted ones are reported. Subdirectories are never reported
+    /// as modified. Hidden entries are skipped.
+    pub fn watch_dirs(mut self, dirs: impl IntoIterator<Item = PathBuf>) -> Self {
+        for dir in dirs {
+            if !self.dirs.contains(&dir) {
+                self.dirs.push(dir);
+            }
+        }
+        self.snapshot = self.scan();
+        self
+    }
+
+    /// Never reports changes to `paths`, e.g. the export's own output files.
+    pub fn ignore(mut self, paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        self.ignored.extend(paths);
+        self.snapshot = self.scan();
+        self
+    }
+
+    /// Sets how long changes must settle before they are reported.
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Sets the interval between scans.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Scans once and returns the changes since the last call, without waiting.
+    pub fn poll(&mut self) -> Changes {
+        let current = self.scan();
+        let changes = diff(&self.snapshot, &current);
+        self.snapshot = current;
+        changes
+    }
+
+    /// Blocks until something changes and then stays unchanged for the
+    /// debounce period, and returns the accumulated changes.
+    ///
+    /// Edits that are reverted before they settle are not reported.
+    pub fn wait_for_changes(&mut self) -> Changes {
+        let mut latest = self.snapshot.clone();
+        let mut last_change: Option<Instant> = None;
+
+        loop {
+            thread::sleep(self.poll_interval);
+
+            let current = self.scan();
+            if current != latest {
+                latest = current;
+                last_change = Some(Instant::now());
+                continue;
+            }
+
+            if let Some(since) = last_change
+                && since.elapsed() >= self.debounce
+            {
+                let changes = diff(&self.snapshot, &latest);
+                if changes.is_empty() {
+                    last_change = None;
+                    continue;
+                }
+                self.snapshot = latest;
+                return changes;
+            }
+        }
+    }
+
+    fn scan(&self) -> Snapshot {
+        let mut snapshot = Snapshot::new();
+
+        for file in &self.files {
+            self.record(&mut snapshot, file);
+        }
+
+        for dir in &self.dirs {
+            let Ok(entries) = fs::read_dir(dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                if entry.file_name().to_string_lossy().starts_with('.') {
+                    continue;
+                }
+                match entry.file_type() {
+                    Ok(kind) if kind.is_file() => self.record(&mut snapshot, &entry.path()),
+                    Ok(kind) if kind.is_dir() => {
+                        // Only their presence matters; their contents are scanned on their own
+                        let stamp = FileStamp {
+                            modified: None,
+                            len: 0,
+                        };
+                        snapshot.insert(entry.path(), stamp);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        snapshot
+    }
+
+    fn record(&self, snapshot: &mut Snapshot, path: &Path) {
+        if self.ignored.contains(path) {
+            return;
+        }
+        if let Ok(metadata) = fs::metadata(path)
+            && metadata.is_file()
+        {
+            let stamp = FileStamp {
+                modified: metadata.modified().ok(),
+                len: metadata.len(),
+            };
+            snapshot.insert(path.to_path_buf(), stamp);
+        }
+    }
+}
+
+fn diff(before: &Snapshot, after: &Snapshot) -> Changes {
+    let mut changes = Changes::default();
+
+    for (path, stamp) in after {
+        match before.get(path) {
+            None => changes.added.push(path.clone()),
+            Some(previous) if previous != stamp => changes.modified.push(path.clone()),
+            Some(_) => {}
+        }
+    }
+    changes.removed = before
+        .keys()
+        .filter(|path| !after.contains_key(*path))
+        .cloned()
+        .collect();
+
+    changes
+}